@@ -0,0 +1,409 @@
+use std::{error::Error, fmt};
+
+use crate::{response::AppResult, MyError};
+
+/*
+    서비스 안쪽 코드는 Box<dyn Error + Send + Sync>로 편하게 에러를 던지고,
+    handler 경계에서만 MyError로 바꾼다.
+
+    그냥 전부 Internal로 바꾸면 404가 500이 되어버리니까,
+    source chain을 따라가면서 MyError / sqlx::Error / redis::RedisError가 있으면 그걸 살린다.
+*/
+
+impl MyError {
+    pub fn from_opaque(err: Box<dyn Error + Send + Sync>) -> MyError {
+        // 맨 위가 바로 우리가 아는 타입이면 소유권째로 꺼낼 수 있다.
+        let err = match err.downcast::<MyError>() {
+            Ok(e) => return *e,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<sqlx::Error>() {
+            Ok(e) => return MyError::SQLError(*e),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<redis::RedisError>() {
+            Ok(e) => return MyError::RedisError(*e),
+            Err(err) => err,
+        };
+
+        // 더 안쪽은 참조밖에 못 얻는다. variant만 살리고 메시지로 다시 만든다. (recover 참고)
+        if let Some(recovered) = walk_chain(err.as_ref()).into_iter().find_map(recover) {
+            return recovered;
+        }
+
+        MyError::Internal(render_chain(err.as_ref()).into_context())
+    }
+}
+
+// 참조만 있으니 원래 에러를 그대로 옮길 수는 없다. 그래서 이렇게 다시 만든다.
+// - sqlx::Error는 Clone이 안 되니까 원래 variant가 뭐였든 sqlx::Error::Protocol(원래 메시지)가 된다.
+//   RowNotFound 같은 sqlx variant로 분기하려면 chain 맨 위에서 꺼내야 한다.
+// - redis::RedisError는 kind는 남고 메시지는 detail로 들어간다.
+fn recover(e: &(dyn Error + 'static)) -> Option<MyError> {
+    if let Some(e) = e.downcast_ref::<MyError>() {
+        return Some(match e {
+            MyError::SQLError(e) => MyError::SQLError(sqlx::Error::Protocol(e.to_string())),
+            MyError::RedisError(e) => MyError::RedisError(copy_redis_error(e)),
+            MyError::Forbidden => MyError::Forbidden,
+            MyError::NotFound => MyError::NotFound,
+            MyError::Unauthorized => MyError::Unauthorized,
+            MyError::Internal(context) => MyError::Internal(context.clone()),
+        });
+    }
+    if let Some(e) = e.downcast_ref::<sqlx::Error>() {
+        return Some(MyError::SQLError(sqlx::Error::Protocol(e.to_string())));
+    }
+    if let Some(e) = e.downcast_ref::<redis::RedisError>() {
+        return Some(MyError::RedisError(copy_redis_error(e)));
+    }
+    None
+}
+
+// RedisError는 Clone이 안 되니까 kind와 메시지만 옮긴다.
+fn copy_redis_error(e: &redis::RedisError) -> redis::RedisError {
+    redis::RedisError::from((e.kind(), "Recovered from error chain", e.to_string()))
+}
+
+/*
+    source()를 그냥 따라가면 버그로 chain이 순환하거나 엄청 깊을 때 끝나지 않는다.
+    그래서 chain은 항상 아래 함수들로만 걷는다.
+
+    - 최대 MAX_CHAIN_DEPTH 단계까지만 본다.
+    - 이미 본 에러(주소와 타입 기준)가 다시 나오면 순환으로 보고 멈춘다.
+    - 메시지가 너무 길면 MAX_MESSAGE_LEN에서 자른다.
+*/
+
+const MAX_CHAIN_DEPTH: usize = 16;
+const MAX_MESSAGE_LEN: usize = 512;
+
+#[derive(Debug)]
+pub struct ChainReport {
+    pub messages: Vec<String>,
+    pub depth_truncated: bool,
+    pub cycle_detected: bool,
+}
+
+impl ChainReport {
+    // MyError::Internal에 넣을 때는 잘림/순환 여부도 메시지로 남긴다.
+    fn into_context(self) -> Vec<String> {
+        let mut context = self.messages;
+        if self.cycle_detected {
+            context.push("<cycle detected>".to_string());
+        }
+        if self.depth_truncated {
+            context.push(format!("<truncated after {MAX_CHAIN_DEPTH} levels>"));
+        }
+        context
+    }
+}
+
+impl fmt::Display for ChainReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.messages.join(": "))?;
+        if self.cycle_detected {
+            write!(f, " (cycle detected)")?;
+        }
+        if self.depth_truncated {
+            write!(f, " (truncated after {MAX_CHAIN_DEPTH} levels)")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn render_chain(err: &(dyn Error + 'static)) -> ChainReport {
+    let chain = walk_chain(err);
+    // walk_chain이 멈춘 다음 에러를 보면 왜 멈췄는지 알 수 있다.
+    let next = chain.last().and_then(|e| e.source());
+    let cycle_detected = next.is_some_and(|n| chain.iter().any(|seen| same_error(*seen, n)));
+    let depth_truncated = next.is_some() && !cycle_detected;
+    ChainReport {
+        messages: chain.iter().map(|e| truncate_message(e.to_string())).collect(),
+        depth_truncated,
+        cycle_detected,
+    }
+}
+
+// 순환이 없고 MAX_CHAIN_DEPTH를 넘지 않는 chain. 맨 앞이 err 자신이다.
+fn walk_chain<'a>(err: &'a (dyn Error + 'static)) -> Vec<&'a (dyn Error + 'static)> {
+    let mut chain: Vec<&'a (dyn Error + 'static)> = Vec::new();
+    let mut current = Some(err);
+    while let Some(e) = current {
+        if chain.len() == MAX_CHAIN_DEPTH || chain.iter().any(|seen| same_error(*seen, e)) {
+            break;
+        }
+        chain.push(e);
+        current = e.source();
+    }
+    chain
+}
+
+// 주소만 비교하면 안된다. struct Wrap(MyError)처럼 source()가 offset 0의 field를 돌려주면
+// 부모와 자식의 주소가 같다. 그래서 vtable까지 같을 때(= 같은 타입)만 같은 에러로 본다.
+// 같은 타입인데 vtable이 codegen unit마다 따로 생기면 순환을 놓칠 수 있지만,
+// 그때는 MAX_CHAIN_DEPTH에서 멈추니까 괜찮다.
+fn same_error(a: &(dyn Error + 'static), b: &(dyn Error + 'static)) -> bool {
+    std::ptr::eq(a as *const dyn Error, b as *const dyn Error)
+}
+
+fn truncate_message(mut message: String) -> String {
+    if message.len() > MAX_MESSAGE_LEN {
+        let mut end = MAX_MESSAGE_LEN;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+        message.push_str("...");
+    }
+    message
+}
+
+// handler에서 result.app_err()? 로 바로 쓰기 위한 extension trait.
+pub trait IntoAppError<T> {
+    fn app_err(self) -> AppResult<T>;
+}
+
+impl<T, E> IntoAppError<T> for Result<T, E>
+where
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn app_err(self) -> AppResult<T> {
+        self.map_err(|e| MyError::from_opaque(e.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, OnceLock};
+
+    // source()가 inline field를 돌려주는 wrapper. repr(C)라 inner가 offset 0에 있어서
+    // 부모와 자식의 주소가 같다.
+    #[derive(Debug)]
+    #[repr(C)]
+    struct Wrap<E> {
+        inner: E,
+        name: &'static str,
+    }
+
+    fn wrap<E>(name: &'static str, inner: E) -> Wrap<E> {
+        Wrap { inner, name }
+    }
+
+    impl<E: Error + 'static> fmt::Display for Wrap<E> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.name)
+        }
+    }
+
+    impl<E: Error + 'static> Error for Wrap<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.inner)
+        }
+    }
+
+    // Arc로 자기 자신을 가리키게 만들 수 있는 test 전용 에러.
+    // 만든 다음에 한 번만 set하면 되니까 OnceLock이면 충분하다. (cycle이라 일부러 leak된다)
+    #[derive(Debug)]
+    struct Cyclic(OnceLock<Arc<Cyclic>>);
+
+    impl Cyclic {
+        // dyn Error로 바꾸는 곳이 여러 군데면 codegen unit마다 vtable이 따로 생길 수 있다.
+        // 그러면 same_error가 순환을 한 칸 늦게 알아채니까, 테스트에서는 여기 한 곳에서만 바꾼다.
+        fn as_dyn(&self) -> &(dyn Error + 'static) {
+            self
+        }
+    }
+
+    impl fmt::Display for Cyclic {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "cyclic")
+        }
+    }
+
+    impl Error for Cyclic {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.0.get().map(|next| next.as_dyn())
+        }
+    }
+
+    // 매번 새 에러를 만들어서 끝없이 깊어지는 chain.
+    #[derive(Debug)]
+    struct Endless(usize);
+
+    impl fmt::Display for Endless {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "level {}", self.0)
+        }
+    }
+
+    impl Error for Endless {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(Box::leak(Box::new(Endless(self.0 + 1))))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Message(String);
+
+    impl fmt::Display for Message {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for Message {}
+
+    // source를 Box로 들고 있는 wrapper.
+    #[derive(Debug)]
+    struct Boxed(&'static str, Box<dyn Error + Send + Sync>);
+
+    impl fmt::Display for Boxed {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for Boxed {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self.1.as_ref())
+        }
+    }
+
+    fn boxed<E: Error + Send + Sync + 'static>(name: &'static str, inner: E) -> Boxed {
+        Boxed(name, Box::new(inner))
+    }
+
+    #[test]
+    fn top_level_errors_are_moved_out_as_is() {
+        assert!(matches!(MyError::from_opaque(Box::new(MyError::Forbidden)), MyError::Forbidden));
+        let recovered = MyError::from_opaque(Box::new(sqlx::Error::RowNotFound));
+        assert!(matches!(recovered, MyError::SQLError(sqlx::Error::RowNotFound)));
+    }
+
+    #[test]
+    fn recovers_my_error_through_two_inline_wrappers() {
+        let err = wrap("wrap2", wrap("wrap", MyError::NotFound));
+        let recovered = MyError::from_opaque(Box::new(err));
+        assert!(matches!(recovered, MyError::NotFound));
+        assert_eq!(recovered.status_code(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn recovers_my_error_through_two_boxed_wrappers() {
+        let err = boxed("outer", boxed("middle", MyError::Unauthorized));
+        let recovered = MyError::from_opaque(Box::new(err));
+        assert!(matches!(recovered, MyError::Unauthorized));
+        assert_eq!(recovered.status_code(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn recovers_through_mixed_boxed_and_inline_wrappers() {
+        let err = boxed("outer", wrap("middle", MyError::Forbidden));
+        assert!(matches!(MyError::from_opaque(Box::new(err)), MyError::Forbidden));
+    }
+
+    #[test]
+    fn nested_sqlx_errors_become_protocol_with_the_original_message() {
+        let err = boxed("outer", boxed("middle", sqlx::Error::RowNotFound));
+        let recovered = MyError::from_opaque(Box::new(err));
+        match &recovered {
+            MyError::SQLError(sqlx::Error::Protocol(message)) => {
+                assert_eq!(message, &sqlx::Error::RowNotFound.to_string());
+            }
+            other => panic!("expected SQLError(Protocol), got {other:?}"),
+        }
+        assert_eq!(recovered.status_code(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn nested_redis_errors_keep_their_kind() {
+        let redis_err = redis::RedisError::from((redis::ErrorKind::IoError, "connection lost"));
+        let recovered = MyError::from_opaque(Box::new(boxed("outer", wrap("middle", redis_err))));
+        match recovered {
+            MyError::RedisError(e) => assert_eq!(e.kind(), redis::ErrorKind::IoError),
+            other => panic!("expected RedisError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unknown_chains_become_internal_with_every_message() {
+        let err = boxed("outer", wrap("middle", std::io::Error::other("disk full")));
+        let recovered = MyError::from_opaque(Box::new(err));
+        match &recovered {
+            MyError::Internal(context) => assert_eq!(context, &vec!["outer", "middle", "disk full"]),
+            other => panic!("expected Internal, got {other:?}"),
+        }
+        assert_eq!(recovered.status_code(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn app_err_converts_any_boxable_error() {
+        let io: Result<(), std::io::Error> = Err(std::io::Error::other("boom"));
+        assert!(matches!(io.app_err(), Err(MyError::Internal(context)) if context == vec!["boom"]));
+
+        let known: Result<(), MyError> = Err(MyError::NotFound);
+        assert!(matches!(known.app_err(), Err(MyError::NotFound)));
+
+        let fine: Result<u8, MyError> = Ok(1);
+        assert!(matches!(fine.app_err(), Ok(1)));
+    }
+
+    #[test]
+    fn inline_wrappers_are_not_mistaken_for_cycles() {
+        let err = wrap("wrap2", wrap("wrap", MyError::NotFound));
+        let report = render_chain(&err);
+        assert_eq!(report.messages, vec!["wrap2", "wrap", "Not Found"]);
+        assert!(!report.cycle_detected);
+        assert!(!report.depth_truncated);
+    }
+
+    #[test]
+    fn arc_self_cycle_terminates() {
+        let a = Arc::new(Cyclic(OnceLock::new()));
+        a.0.set(a.clone()).unwrap();
+
+        let report = render_chain(a.as_dyn());
+        assert_eq!(report.messages, vec!["cyclic"]);
+        assert!(report.cycle_detected);
+        assert!(!report.depth_truncated);
+        assert_eq!(report.to_string(), "cyclic (cycle detected)");
+    }
+
+    #[test]
+    fn two_node_arc_cycle_terminates() {
+        let a = Arc::new(Cyclic(OnceLock::new()));
+        let b = Arc::new(Cyclic(OnceLock::from(a.clone())));
+        a.0.set(b.clone()).unwrap();
+
+        let report = render_chain(a.as_dyn());
+        assert_eq!(report.messages.len(), 2);
+        assert!(report.cycle_detected);
+    }
+
+    #[test]
+    fn deep_chains_are_truncated() {
+        let report = render_chain(&Endless(0));
+        assert_eq!(report.messages.len(), MAX_CHAIN_DEPTH);
+        assert_eq!(report.messages.last().unwrap(), "level 15");
+        assert!(report.depth_truncated);
+        assert!(!report.cycle_detected);
+        assert!(report.to_string().ends_with("(truncated after 16 levels)"));
+    }
+
+    #[test]
+    fn long_messages_are_cut_at_a_char_boundary() {
+        // 'é'는 2바이트라 512바이트 지점이 글자 경계에 맞는다. 앞에 'a' 하나를 붙여 어긋나게 한다.
+        let err = Message(format!("a{}", "é".repeat(600)));
+        let message = &render_chain(&err).messages[0];
+        assert!(message.ends_with("..."));
+        let kept = message.trim_end_matches("...");
+        assert_eq!(kept.len(), MAX_MESSAGE_LEN - 1);
+        assert!(kept.starts_with('a') && kept[1..].chars().all(|c| c == 'é'));
+    }
+
+    #[test]
+    fn short_messages_are_kept_whole() {
+        let report = render_chain(&Message("short".to_string()));
+        assert_eq!(report.messages, vec!["short"]);
+    }
+}
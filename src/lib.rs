@@ -0,0 +1,724 @@
+// use core::fmt;
+use std::{error::Error, fmt::{self}};
+
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use chrono::DateTime;
+use chrono::Utc;
+
+pub mod error;
+pub mod number;
+pub mod response;
+
+#[cfg(test)]
+mod proptest_lite;
+
+/*
+    =====================
+    =====================
+    ==== 1. Traits ======
+    =====================
+    =====================
+
+*/
+
+// 러스트의 trait은 scala의 trait과 다르게 다중 상속을 통한 mixin이 불가하다.
+// 대신, '+'연산으로 trait multiple bound를 통해 다중 상속을 흉내낼 수 있다.
+// 다만, rust의 trait은 문법에서 upper/lower/context bound를 제공하지 않는다.
+
+/*
+아래는 내가 trait을 활용해서 spark에 사용한 mixin 이다.
+
+case class dataExample(name: String, age: Int)
+trait CustomSerializable extends Serializable
+
+object SparkAppWithSerializable extends App with CustomSerializable {
+  val spark = SparkSession.builder
+    .appName("SparkAppWithSerializable")
+    .master("local[*]")
+    .getOrCreate()
+
+  val sc = spark.sparkContext
+
+  val data = Seq(Person("Alice", 28), Person("Bob", 32), Person("Catherine", 25))
+  val rdd = sc.parallelize(data)
+
+  val adults = rdd.filter(_.age >= 30).collect()
+
+  adults.foreach(println)
+
+  spark.stop() 
+
+
+위 scala코드에서 extends App with CustomSerializable은 trait bound와 mixin을 동시에 사용한 예제이다.
+upper bound와 혼합하면 아래와 같은 형태가 나온다.
+
+class Zoo[T <: Animal with HasLegs](val animal: T) {
+  def showInfo(): Unit = {
+    println(s"Animal: ${animal.name}, Legs: ${animal.numberOfLegs}")
+  }
+}
+
+rust에서는 upper bound나 trait/mixin 동시 사용은 안되지만, generic을 활용한 multiple bound를 할 수 있다. 아래에서 보자.
+
+use std::fmt::Debug;
+use std::fmt::Display;
+
+fn print_info<T: Display + Debug>(item: T) {
+    println!("Display: {}", item);
+    println!("Debug: {:?}", item);
+}
+
+fn main() {
+    let value = 42;
+    print_info(value);
+}
+
+위의 fn print_into<T: Display + Debug>(item: T){}는 Display와 Debug trait을 동시에 사용하는 예제이다.
+T에 대한 구현이 Display도 가능하고 Debug도 가능하다는 의미다.
+헷갈리면 안되는 것이 위의 구현은 generic을 이용한 타입연산이기 때문에 pointer를 이용한 dynamic dispatch가 아닌 static dispatch이다.
+
+당연히 Golang의 pointer reciever처럼 runtime 참조 또한 가능하다.
+vtable에 포인터를 저장하고  dynamic dispatch를 통한 runtime method calling이 가능하다.
+즉, 다형성을 위한 syntax가 존재하는데, clang처럼 포인터를 저장하고 호출하거나
+golang처럼 "func(*structName) func(){}" 식의 포인터 호출이 아닌 "impl ~ for ~로 호출이 가능하다."
+
+example: impl traitName for structName{}
+다만, GC를 통한 메모리수집이나 다중 상속을 허용하지 않으므로 trait bound와 mixin을 동시에 사용하는 것은 불가능하다.
+
+후... Rust의 trait파트는 너무 쓸말이 많으니 넘어가자.
+*/
+
+/*
+    ====================
+    ====================
+    ==== 2. Enums ======
+    ====================
+    ====================
+*/
+
+// Rust의 enum은 언어에서 지원하는 강력한 타입이다.
+pub enum Number {
+    Odd(i64),
+    Even(i64),
+}
+
+impl Number {
+    // 아래 처럼 if로 해도 되지만, match를 사용해서 깔끔하게 풀 수 있다.
+
+    // fn from_i64(num: i64) -> Self {
+    //     if num % 2 == 0 {
+    //         Number::Even(num),
+    //     } else {
+    //         Number::Odd(num),
+    //     }
+    // }
+
+    pub fn from_i64(num: i64) -> Self {
+        match num % 2 == 0 {
+            true => Number::Even(num),
+            false => Number::Odd(num),
+        }
+    }
+
+    // match num % 2 {
+    //     0 -> Number::Even(num as i32),
+    //     _ -> Number::Odd(num as i32),
+    // }
+    // *ns optimization
+
+    // 어느 variant든 안에 든 값은 같은 i64다.
+    pub fn value(&self) -> i64 {
+        match self {
+            Number::Odd(num) | Number::Even(num) => *num,
+        }
+    }
+}
+
+//  Enum as error types
+/*
+    Rust의 enum은 언어의 지원을 받는다.
+    golang의 const와 iota로 syntatic하게 사용하는 것과는 다르게 enum이라는 타입을 지정해서 사용할 수 있다.
+    아래는 내가 cloud image driver에 사용했던 Golang의 예제이다.
+
+    /// errors.go
+    type DriverError interface {
+        error
+        GetErrLevel() ErrorLevel
+    }
+    type DriverType int
+
+    const (
+        UnknownDriverType DriverType = iota
+        ...
+    )
+    const (
+        apiDriverType                = 100
+        HttpApiDriverType DriverType = iota + apiDriverType
+        ...
+    )
+
+    func (t DriverType) String() string {
+        switch t {
+        case HttpApiDriverType:
+            return "HttpApiDriverType"
+        case GrpcGwDriverType:
+            return "GrpcGwDriverType"
+        case KubeletClientDriverType:
+            return "KubeletClientDriverType"
+        case AwsClientDriverType:
+            return "AwsClientDriverType"
+        default:
+            return "UnknownDriverType"
+        }
+    }
+
+    iota라는 incremental한 선언으로 사용하는 것으로 enum을 지정한다.
+
+    Rust에서는 enum을 사용해서 아래와 같이 사용할 수 있다.
+    한번 보자.
+*/
+
+// derive(debug)매크로 선언을 통해 Debugging을 위한 출력을 사용할 수 있다.
+#[derive(Debug)]
+pub enum MyError {
+    SQLError(sqlx::Error),
+    RedisError(redis::RedisError),
+    Forbidden,
+    NotFound,
+    Unauthorized,
+    // 어디서 왔는지 모르는 에러. 원인 chain을 메시지로 들고 있는다.
+    Internal(Vec<String>),
+}
+
+impl fmt::Display for MyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MyError::SQLError(e) => write!(f, "SQL Error: {e}"),
+            // MyError::SQLError(e) => write!(f, format!("SQL Error: {e}")),
+            MyError::RedisError(e) => write!(f, "Redis Error: {e}"),
+            MyError::Forbidden => write!(f, "Forbidden"),
+            MyError::NotFound => write!(f, "Not Found"),
+            MyError::Unauthorized => write!(f, "Unauthorized"),
+            MyError::Internal(context) => write!(f, "Internal Error: {}", context.join(": ")),
+        }
+    }
+}
+/*
+    각 에러에 대해 switch-case를 사용했던 것과 같이 match를 사용해서 처리한다.
+    특히 Debug 매크로를 사용하면 별도의 출력을 위한 코드를 작성하지않아도된다.
+    하지만 Debug 매크로를 사용하려면, 강타입의 trait 구현을 위해 string변환하는 fmt::Display를 구현해주어야한다.
+    rust에선 derive(Debug)의 구현은 fmt::Display를 강제한다.
+    fmt::Display를 구현하면, println!("{}", MyError::Forbidden)과 같이 사용할 수 있다.
+
+    * format!: 포맷된 문자열을 사용하는 매크로. js의 `hello {$world}`와 같은 템플릿 리터럴과 유사하다.
+    * write!: 출력 대상이 std::fmt::write를 구현한 버퍼나 파일같은 대상에 쓰기를 수행하는 매크로.
+    * fmt::Formatter: 출력을 위한 구조체, {}나 {:?}와 같은 String placeholder를 사용할 수 있다.
+    * fmt::Result: write!나 format!의 결과를 반환한다. Result를 반환하는 이유는 Rust의 에러처리 방식 때문임
+
+    Error trait을 자세히 보면 아래와 같음.
+
+    pub trait Error: Debug + Display {
+        fn description(&self) -> &str { ... }
+        fn source(&self) -> Option<&(dyn Error + 'static)> { ...}
+    }
+    모두 구현해야하나? 아님, Optional하기 때문에 필요한 것만 구현하면 된다.
+*/
+
+// 아래 trait impl로 Error trait을 구현한다.
+impl Error for MyError{}
+
+// 다른 web app은 어떻게 했을까?
+// Axum
+impl IntoResponse for MyError {
+    fn into_response(self) -> Response {
+        // status는 아래 "3. Macros"의 error_taxonomy!가 만들어준 status_code()를 쓴다.
+        let body = match &self {
+            MyError::SQLError(e) => format!("SQL Error {e}"),
+            MyError::RedisError(e) => format!("REDIS Error {e}"),
+            // context는 로그용이다. client에게는 보여주지 않는다.
+            MyError::Internal(_) => "Internal Error".to_string(),
+            other => other.to_string(),
+        };
+        (self.status_code(), body).into_response()
+    }
+}
+
+// Enum as wrapper types
+/*
+    언어에서 기본 제공하는 Enum을 좀 더 누려보자.
+    password에 secured옵션과 unsecured 옵션을 나눈다.
+*/
+
+// 아래 Password와 PasswordEnum은 설명용 예제라 crate 안에서 부르는 곳이 없다.
+#[allow(dead_code)]
+struct Password {
+    password: String,
+    created_at: DateTime<Utc>,
+}
+
+// password는 어떤 출력에도 그대로 나오면 안된다.
+// derive(Debug)를 쓰면 field가 전부 찍히니까 Debug도 직접 구현한다.
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Password(<redacted>, created_at: {:?})", self.created_at)
+    }
+}
+
+// 안쪽의 Password가 이미 가려지니까 enum은 derive해도 된다.
+#[allow(dead_code)]
+#[derive(Debug)]
+enum PasswordEnum {
+    Secured(Password),
+    Unsecured(Password),
+}
+// enum에 trait을 붙여서 출력이 가능하게 해보자.
+// 아래 코드는 메서드를 
+// Dynamic dispatch
+impl fmt::Display for PasswordEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 글자 수만큼 '*'를 찍으면 길이가 새니까 고정된 길이로 가린다.
+        match self {
+            PasswordEnum::Secured(_) => write!(f, "********"),
+            PasswordEnum::Unsecured(_) => write!(f, "******** (unsecured)"),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl PasswordEnum {
+    fn is_secured(&self) -> bool {
+        match self {
+            PasswordEnum::Secured(_) => true,
+            PasswordEnum::Unsecured(_) => false,
+        }
+    }
+}
+
+/*
+    secret을 들고 있는 타입은 전부 SecretType을 구현한다.
+    그러면 tests의 redaction_audit가 sentinel 값을 secret 자리에 넣어 만들어 보고,
+    {:?}와 {} 어디에도 sentinel이 안 나오는지 확인한다.
+
+    구현만 하고 registry에 등록하지 않으면 completeness test가 실패한다.
+    테스트에서만 쓰는 fixture라서 trait과 impl 모두 cfg(test)로 막아둔다.
+*/
+#[cfg(test)]
+pub trait SecretType: fmt::Debug + Sized {
+    const NAME: &'static str;
+
+    // secret을 넣어서 만들 수 있는 모양(variant)을 전부 만들어 돌려준다.
+    fn samples(secret: &str) -> Vec<Self>;
+
+    // Display가 있는 타입은 여기서 렌더링해서 같이 검사 받는다.
+    fn display(&self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+impl SecretType for Password {
+    const NAME: &'static str = "Password";
+
+    fn samples(secret: &str) -> Vec<Self> {
+        vec![Password { password: secret.to_string(), created_at: Utc::now() }]
+    }
+}
+
+#[cfg(test)]
+impl SecretType for PasswordEnum {
+    const NAME: &'static str = "PasswordEnum";
+
+    fn samples(secret: &str) -> Vec<Self> {
+        let mut samples = Vec::new();
+        for password in Password::samples(secret) {
+            samples.push(PasswordEnum::Secured(password));
+        }
+        for password in Password::samples(secret) {
+            samples.push(PasswordEnum::Unsecured(password));
+        }
+        samples
+    }
+
+    fn display(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+/*
+    ====================
+    ====================
+    ==== 3. Macros =====
+    ====================
+    ====================
+
+    위에서 쓴 derive(Debug)도 매크로지만, 이건 컴파일러에 들어있는 built-in derive라서 그냥 쓰면 된다.
+    derive(MyTrait)처럼 직접 derive를 만들려면 proc macro라서 별도 crate가 필요하다.
+    여기서는 별도 crate 없이 macro_rules!로 만드는 declarative macro를 써보자.
+
+    MyError에 variant가 하나 추가될 때마다 status code, metrics label, transient 여부, 설명을
+    각각 다른 match에서 고쳐줘야 한다. 하나라도 까먹으면 조용히 틀린 값이 나간다.
+    그래서 variant당 한 줄(한 블록)로 전부 적고, match들은 매크로가 만들게 한다.
+
+    매크로가 만드는 match에는 `_ =>` 가 없다.
+    그래서 enum에 variant를 추가하고 taxonomy에 안 적으면 non-exhaustive match로 컴파일이 안된다.
+    필드를 하나 빼먹어도 매크로 패턴에 안 맞아서 컴파일이 안된다.
+*/
+
+// 한 variant에 대한 taxonomy 정보. catalog로 전부 훑어볼 때 쓴다.
+pub struct ErrorEntry {
+    pub code: &'static str,
+    pub status: StatusCode,
+    // true면 재시도해볼 만한 에러다. when 조건이 붙은 variant는 payload를 보고 정한다.
+    pub transient: bool,
+    pub description: &'static str,
+    pub label: &'static str,
+}
+
+macro_rules! error_taxonomy {
+    (
+        $enum:ident {
+            $(
+                $variant:ident $( ( $($payload:tt)* ) )? => {
+                    status: $status:expr,
+                    code: $code:literal,
+                    transient: $transient:literal $(when $when:expr)?,
+                    description: $description:literal,
+                    label: $label:literal $(,)?
+                }
+            ),* $(,)?
+        }
+    ) => {
+        // when 조건에서 쓰려고 payload에 이름을 붙이면, 그 이름을 안 쓰는 나머지 match에서 경고가 난다.
+        #[allow(unused_variables)]
+        impl $enum {
+            pub const CATALOG: &'static [ErrorEntry] = &[
+                $(
+                    ErrorEntry {
+                        code: $code,
+                        status: $status,
+                        transient: $transient,
+                        description: $description,
+                        label: $label,
+                    },
+                )*
+            ];
+
+            pub fn status_code(&self) -> StatusCode {
+                match self {
+                    $( $enum::$variant $( ( $($payload)* ) )? => $status, )*
+                }
+            }
+
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $( $enum::$variant $( ( $($payload)* ) )? => $code, )*
+                }
+            }
+
+            // 잠깐 기다렸다 다시 시도하면 될 수도 있는 에러인가?
+            pub fn is_transient(&self) -> bool {
+                match self {
+                    $( $enum::$variant $( ( $($payload)* ) )? => $transient $(&& $when)?, )*
+                }
+            }
+
+            pub fn describe(&self) -> &'static str {
+                match self {
+                    $( $enum::$variant $( ( $($payload)* ) )? => $description, )*
+                }
+            }
+
+            pub fn metric_label(&self) -> &'static str {
+                match self {
+                    $( $enum::$variant $( ( $($payload)* ) )? => $label, )*
+                }
+            }
+        }
+
+        // code나 label을 복붙하다 겹치면 대시보드에서 두 에러가 섞인다. 컴파일 타임에 막는다.
+        // (const fn에서는 &str == 을 못 써서 바이트로 직접 비교한다.)
+        const _: () = {
+            const fn str_eq(a: &str, b: &str) -> bool {
+                let (a, b) = (a.as_bytes(), b.as_bytes());
+                if a.len() != b.len() {
+                    return false;
+                }
+                let mut i = 0;
+                while i < a.len() {
+                    if a[i] != b[i] {
+                        return false;
+                    }
+                    i += 1;
+                }
+                true
+            }
+
+            let catalog = $enum::CATALOG;
+            let mut i = 0;
+            while i < catalog.len() {
+                let mut j = i + 1;
+                while j < catalog.len() {
+                    assert!(!str_eq(catalog[i].code, catalog[j].code), "duplicate error code in taxonomy");
+                    assert!(!str_eq(catalog[i].label, catalog[j].label), "duplicate metric label in taxonomy");
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
+    };
+}
+
+// transient: true when <조건> 으로 쓰면 payload를 보고 정한다.
+// RedisError는 연결이 끊기거나 timeout 난 경우만 다시 해볼 만하다.
+// SQLError에는 RowNotFound, ColumnNotFound, decode 에러처럼 다시 해도 똑같이 실패하는 게 섞여 있으니 false로 둔다.
+error_taxonomy! {
+    MyError {
+        SQLError(_) => {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "SQL_ERROR",
+            transient: false,
+            description: "The database returned an error.",
+            label: "sql",
+        },
+        RedisError(e) => {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "REDIS_ERROR",
+            transient: true when e.is_io_error() || e.is_timeout(),
+            description: "Redis returned an error.",
+            label: "redis",
+        },
+        Forbidden => {
+            status: StatusCode::FORBIDDEN,
+            code: "FORBIDDEN",
+            transient: false,
+            description: "The caller is not allowed to perform this action.",
+            label: "forbidden",
+        },
+        NotFound => {
+            status: StatusCode::NOT_FOUND,
+            code: "NOT_FOUND",
+            transient: false,
+            description: "The requested resource does not exist.",
+            label: "not_found",
+        },
+        Unauthorized => {
+            status: StatusCode::UNAUTHORIZED,
+            code: "UNAUTHORIZED",
+            transient: false,
+            description: "The caller is not authenticated.",
+            label: "unauthorized",
+        },
+        Internal(_) => {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "INTERNAL",
+            transient: false,
+            description: "An unexpected internal error occurred.",
+            label: "internal",
+        },
+    }
+}
+
+
+
+/*
+    =====================
+    =====================
+    ==== 4. Patterns ====
+    =====================
+    =====================
+*/
+
+/*
+    references:
+        - my head with
+        - cloudflare
+        - shuttle
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod redaction_audit {
+        use super::*;
+
+        const SENTINEL: &str = "sentinel-hunter2-3f9a";
+
+        fn audit<T: SecretType>() -> &'static str {
+            for sample in T::samples(SENTINEL) {
+                let debug = format!("{sample:?}");
+                assert!(!debug.contains(SENTINEL), "{} leaks its secret in Debug: {debug}", T::NAME);
+                if let Some(display) = sample.display() {
+                    assert!(!display.contains(SENTINEL), "{} leaks its secret in Display: {display}", T::NAME);
+                }
+            }
+            T::NAME
+        }
+
+        // 새 secret 타입은 여기에 추가한다.
+        fn registry() -> Vec<&'static str> {
+            vec![audit::<Password>(), audit::<PasswordEnum>()]
+        }
+
+        #[test]
+        fn no_secret_type_leaks_its_sentinel() {
+            assert!(!registry().is_empty());
+        }
+
+        // `impl SecretType for X`, `impl crate::SecretType for X`, `impl<T> SecretType for X<T>` 같은 줄에서 X를 꺼낸다.
+        fn implemented_type(line: &str) -> Option<&str> {
+            let header = line.trim_start().strip_prefix("impl")?;
+            // 이 파일 안의 문자열 자체에 걸리지 않게 나눠서 붙인다.
+            let (before, after) = header.split_once(concat!("SecretType", " for "))?;
+
+            // trait 앞에는 generic과 path(crate::, super:: ...)만 올 수 있다.
+            let mut before = before.trim_start();
+            if before.starts_with('<') {
+                let mut open = 0;
+                let end = before.char_indices().find_map(|(i, c)| {
+                    match c {
+                        '<' => open += 1,
+                        '>' => open -= 1,
+                        _ => {}
+                    }
+                    (open == 0).then_some(i + 1)
+                })?;
+                before = &before[end..];
+            }
+            let path = before.trim();
+            if !(path.is_empty() || path.ends_with("::")) || path.contains(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')) {
+                return None;
+            }
+
+            // 타입도 path일 수 있다. 마지막 segment만 쓴다.
+            let ty = after.trim_start();
+            let end = ty.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).unwrap_or(ty.len());
+            ty[..end].rsplit("::").next().filter(|name| !name.is_empty())
+        }
+
+        fn rust_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    rust_files(&path, files);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    files.push(path);
+                }
+            }
+        }
+
+        #[test]
+        fn impl_headers_are_matched_loosely() {
+            assert_eq!(implemented_type(concat!("impl Secret", "Type for Password {")), Some("Password"));
+            assert_eq!(implemented_type(concat!("impl crate::Secret", "Type for Token {")), Some("Token"));
+            assert_eq!(implemented_type(concat!("    impl<T: Debug> super::Secret", "Type for Wrapper<T> {")), Some("Wrapper"));
+            assert_eq!(implemented_type(concat!("impl<T> Secret", "Type for crate::auth::Key<T>")), Some("Key"));
+            assert_eq!(implemented_type(concat!("// impl Secret", "Type for Commented")), None);
+            assert_eq!(implemented_type(concat!("impl NotSecret", "Type for Other {")), None);
+        }
+
+        // src 아래 .rs 파일을 전부 찾아서 SecretType 구현을 registry와 비교한다.
+        // 파일 목록을 손으로 들고 있지 않으니까 새 모듈에 구현을 추가해도 여기서 걸린다.
+        #[test]
+        fn every_secret_type_is_registered() {
+            let src = std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src"));
+            let mut files = Vec::new();
+            rust_files(src, &mut files);
+            // 하위 디렉터리까지 내려갔는지 본다.
+            assert!(files.contains(&src.join("lib.rs")));
+            assert!(files.iter().any(|path| path.parent() != Some(src)), "no files found below {}", src.display());
+
+            let sources: Vec<String> = files.iter().map(|path| std::fs::read_to_string(path).unwrap()).collect();
+            let mut implemented: Vec<&str> = sources.iter().flat_map(|source| source.lines().filter_map(implemented_type)).collect();
+
+            let mut registered = registry();
+            implemented.sort_unstable();
+            registered.sort_unstable();
+            assert_eq!(implemented, registered);
+        }
+    }
+
+    mod taxonomy {
+        use super::*;
+
+        // 호출하지는 않는다. variant가 추가되면 여기 match가 non-exhaustive라 컴파일이 안된다.
+        // 그때 아래 samples()에도 새 variant를 하나 넣어준다.
+        fn _exhaustive(e: &MyError) {
+            match e {
+                MyError::SQLError(_)
+                | MyError::RedisError(_)
+                | MyError::Forbidden
+                | MyError::NotFound
+                | MyError::Unauthorized
+                | MyError::Internal(_) => {}
+            }
+        }
+
+        // variant마다 하나씩.
+        fn samples() -> Vec<MyError> {
+            vec![
+                MyError::SQLError(sqlx::Error::RowNotFound),
+                MyError::RedisError(redis::RedisError::from((redis::ErrorKind::IoError, "down"))),
+                MyError::Forbidden,
+                MyError::NotFound,
+                MyError::Unauthorized,
+                MyError::Internal(vec![]),
+            ]
+        }
+
+        #[test]
+        fn catalog_has_one_row_per_variant() {
+            let mut produced: Vec<_> = samples().iter().map(MyError::code).collect();
+            let mut catalog: Vec<_> = MyError::CATALOG.iter().map(|entry| entry.code).collect();
+            produced.sort_unstable();
+            catalog.sort_unstable();
+            assert_eq!(produced, catalog);
+        }
+
+        // 중복은 매크로의 const assertion이 막는다. 여기서는 모양을 본다.
+        // code는 client에게 나가는 SCREAMING_SNAKE, label은 metrics에 붙는 snake_case여야 한다.
+        #[test]
+        fn codes_and_labels_are_well_formed() {
+            for entry in MyError::CATALOG {
+                assert!(!entry.code.is_empty() && entry.code.bytes().all(|b| b.is_ascii_uppercase() || b == b'_'), "{}", entry.code);
+                assert!(!entry.label.is_empty() && entry.label.bytes().all(|b| b.is_ascii_lowercase() || b == b'_'), "{}", entry.label);
+                assert!(entry.description.ends_with('.'), "{}", entry.description);
+            }
+        }
+
+        #[test]
+        fn methods_agree_with_catalog_row() {
+            for e in samples() {
+                let entry = MyError::CATALOG.iter().find(|entry| entry.code == e.code()).unwrap();
+                assert_eq!(e.status_code(), entry.status);
+                // when 조건이 있으면 catalog가 true여도 payload에 따라 false일 수 있다.
+                assert!(!e.is_transient() || entry.transient, "{}", entry.code);
+                assert_eq!(e.describe(), entry.description);
+                assert_eq!(e.metric_label(), entry.label);
+            }
+        }
+
+        #[test]
+        fn response_status_comes_from_taxonomy() {
+            for e in samples() {
+                let status = e.status_code();
+                assert_eq!(e.into_response().status(), status);
+            }
+        }
+
+        #[test]
+        fn backend_errors_are_not_blindly_retryable() {
+            assert!(!MyError::SQLError(sqlx::Error::RowNotFound).is_transient());
+            assert!(!MyError::SQLError(sqlx::Error::ColumnNotFound("id".into())).is_transient());
+        }
+
+        #[test]
+        fn redis_errors_are_transient_only_when_the_connection_failed() {
+            let redis = |e: redis::RedisError| MyError::RedisError(e);
+            assert!(redis((redis::ErrorKind::IoError, "connection dropped").into()).is_transient());
+            assert!(redis(std::io::Error::from(std::io::ErrorKind::TimedOut).into()).is_transient());
+            assert!(!redis((redis::ErrorKind::ResponseError, "WRONGTYPE").into()).is_transient());
+            assert!(!redis((redis::ErrorKind::AuthenticationFailed, "bad password").into()).is_transient());
+        }
+    }
+}
@@ -0,0 +1,9 @@
+// Number enum 자체는 lib.rs의 "2. Enums" 파트에 있다.
+// 여기는 Number를 가지고 노는 기능들을 모아둔다.
+pub mod codec;
+pub mod eval;
+
+#[cfg(test)]
+mod differential;
+
+pub use eval::eval;
@@ -0,0 +1,303 @@
+use std::{error::Error, fmt, io};
+
+use crate::Number;
+
+/*
+    Vec<Number>를 캐싱할 때 쓰는 작은 바이너리 포맷.
+
+    [version: u8][count: varint][value: varint]...
+
+    각 value는 zigzag로 부호를 접은 다음, 한 비트 밀어서 제일 아래 비트에 parity를 넣는다.
+    (Odd => 1, Even => 0)
+    zigzag만 해도 i64 범위를 u64로 꽉 채우기 때문에 parity까지 넣으면 65비트가 된다.
+    그래서 varint는 u128로 다루고, 최대 10바이트까지만 허용한다.
+
+    parity는 값에서 다시 계산할 수 있는 정보라 decode할 때 검증용으로 쓴다.
+    둘이 안 맞으면 데이터가 깨진 것이다.
+
+    같은 값은 항상 같은 바이트가 되어야 캐시 key로도 쓸 수 있으니까,
+    0x81 0x00 처럼 쓸데없이 긴 varint는 decode에서 거절한다.
+*/
+
+const VERSION: u8 = 1;
+
+// 65비트 = 7비트 * 9 + 2비트
+const MAX_VARINT_LEN: usize = 10;
+
+#[derive(Debug)]
+pub enum CodecError {
+    UnexpectedEof,
+    UnsupportedVersion(u8),
+    VarintOverflow,
+    NonCanonicalVarint,
+    ParityMismatch { index: u64 },
+    TrailingBytes(usize),
+    Io(io::Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::UnexpectedEof => write!(f, "Unexpected end of input"),
+            CodecError::UnsupportedVersion(v) => write!(f, "Unsupported codec version: {v}"),
+            CodecError::VarintOverflow => write!(f, "Varint overflow"),
+            CodecError::NonCanonicalVarint => write!(f, "Varint is not minimally encoded"),
+            CodecError::ParityMismatch { index } => write!(f, "Parity mismatch at index {index}"),
+            CodecError::TrailingBytes(n) => write!(f, "{n} trailing bytes after last value"),
+            CodecError::Io(e) => write!(f, "IO Error: {e}"),
+        }
+    }
+}
+
+impl Error for CodecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodecError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CodecError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => CodecError::UnexpectedEof,
+            _ => CodecError::Io(e),
+        }
+    }
+}
+
+pub fn encode(numbers: &[Number]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(1 + MAX_VARINT_LEN + numbers.len() * 2);
+    // Vec<u8>에 쓰는 건 실패하지 않는다.
+    encode_to(numbers, &mut buf).expect("writing to Vec<u8> never fails");
+    buf
+}
+
+// value 하나마다 write_all을 한 번씩 부른다.
+// File이나 TcpStream에 바로 쓰면 value마다 syscall이 나가니까 BufWriter로 감싸서 넘긴다.
+pub fn encode_to<W: io::Write>(numbers: &[Number], mut w: W) -> io::Result<()> {
+    w.write_all(&[VERSION])?;
+    write_varint(&mut w, numbers.len() as u128)?;
+    for number in numbers {
+        write_varint(&mut w, pack(number))?;
+    }
+    Ok(())
+}
+
+// slice 전체가 하나의 메시지여야 한다. 뒤에 남는 바이트가 있으면 에러.
+pub fn decode(mut bytes: &[u8]) -> Result<Vec<Number>, CodecError> {
+    let numbers = decode_from(&mut bytes)?;
+    match bytes.len() {
+        0 => Ok(numbers),
+        n => Err(CodecError::TrailingBytes(n)),
+    }
+}
+
+// stream에서는 메시지 하나만 읽고 멈춘다. 뒤에 뭐가 더 있는지는 호출하는 쪽 사정이다.
+// 그래서 안에서 버퍼링하지 않는다. 미리 읽어두면 다음 메시지의 바이트까지 먹어버린다.
+// varint를 한 바이트씩 read_exact로 읽으니까 File이나 TcpStream은 BufReader로 감싸서 넘긴다.
+pub fn decode_from<R: io::Read>(mut r: R) -> Result<Vec<Number>, CodecError> {
+    let mut version = [0u8; 1];
+    r.read_exact(&mut version)?;
+    if version[0] != VERSION {
+        return Err(CodecError::UnsupportedVersion(version[0]));
+    }
+
+    let count = read_varint(&mut r)?;
+    let count = u64::try_from(count).map_err(|_| CodecError::VarintOverflow)?;
+
+    // count는 아직 믿을 수 없는 값이라 미리 할당하지 않는다.
+    let mut numbers = Vec::new();
+    for index in 0..count {
+        numbers.push(unpack(read_varint(&mut r)?, index)?);
+    }
+    Ok(numbers)
+}
+
+fn pack(number: &Number) -> u128 {
+    let value = number.value();
+    let zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    // variant가 pub이라 Number::Odd(2) 같은 것도 만들 수 있다.
+    // variant를 믿고 쓰면 decode가 자기 출력을 깨진 데이터로 보니까, parity는 값에서 다시 계산한다.
+    let parity = match Number::from_i64(value) {
+        Number::Odd(_) => 1,
+        Number::Even(_) => 0,
+    };
+    ((zigzag as u128) << 1) | parity
+}
+
+fn unpack(packed: u128, index: u64) -> Result<Number, CodecError> {
+    let zigzag = (packed >> 1) as u64;
+    let value = ((zigzag >> 1) as i64) ^ -((zigzag & 1) as i64);
+    let number = Number::from_i64(value);
+    let parity = packed & 1;
+    match (number, parity) {
+        (n @ Number::Odd(_), 1) | (n @ Number::Even(_), 0) => Ok(n),
+        _ => Err(CodecError::ParityMismatch { index }),
+    }
+}
+
+fn write_varint<W: io::Write>(w: &mut W, mut value: u128) -> io::Result<()> {
+    let mut buf = [0u8; MAX_VARINT_LEN];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    w.write_all(&buf[..len])
+}
+
+fn read_varint<R: io::Read>(r: &mut R) -> Result<u128, CodecError> {
+    let mut value: u128 = 0;
+    for i in 0..MAX_VARINT_LEN {
+        let mut byte = [0u8; 1];
+        r.read_exact(&mut byte)?;
+        let byte = byte[0];
+
+        // 마지막 바이트는 65비트 중 남은 2비트만 쓸 수 있다.
+        if i == MAX_VARINT_LEN - 1 && byte > 0x03 {
+            return Err(CodecError::VarintOverflow);
+        }
+
+        value |= ((byte & 0x7f) as u128) << (7 * i);
+        if byte & 0x80 == 0 {
+            // 첫 바이트가 아닌데 0이면 앞 바이트에서 끝낼 수 있었다는 뜻이다.
+            if i > 0 && byte == 0 {
+                return Err(CodecError::NonCanonicalVarint);
+            }
+            return Ok(value);
+        }
+    }
+    Err(CodecError::VarintOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proptest_lite::{any_i64, boxed, check, elements, one_of, range, vec_of};
+
+    const EXTREMES: [i64; 7] = [i64::MIN, i64::MAX, i64::MIN + 1, i64::MAX - 1, 0, 1, -1];
+
+    fn values(numbers: &[Number]) -> Vec<i64> {
+        numbers.iter().map(Number::value).collect()
+    }
+
+    fn is_odd(number: &Number) -> bool {
+        matches!(number, Number::Odd(_))
+    }
+
+    #[test]
+    fn round_trips_random_vectors_with_extremes() {
+        let value = one_of(vec![boxed(any_i64()), boxed(elements(&EXTREMES))]);
+        check("CODEC_SEED", 500, vec_of(value, 64), |input: &Vec<i64>| {
+            let numbers: Vec<Number> = input.iter().map(|&v| Number::from_i64(v)).collect();
+
+            let decoded = decode(&encode(&numbers)).unwrap();
+
+            assert_eq!(&values(&decoded), input);
+            assert!(numbers.iter().zip(&decoded).all(|(a, b)| is_odd(a) == is_odd(b)));
+        });
+    }
+
+    #[test]
+    fn round_trips_empty_input() {
+        let encoded = encode(&[]);
+        assert_eq!(encoded, vec![VERSION, 0]);
+        assert!(decode(&encoded).unwrap().is_empty());
+    }
+
+    #[test]
+    fn small_values_encode_in_at_most_two_bytes() {
+        // header(version 1바이트 + count 1바이트)를 빼고 값 하나의 크기만 본다.
+        for v in -4096..4096 {
+            let encoded = encode(&[Number::from_i64(v)]);
+            assert!(encoded.len() - 2 <= 2, "{v} took {} bytes", encoded.len() - 2);
+        }
+        assert_eq!(encode(&[Number::from_i64(4096)]).len() - 2, 3);
+    }
+
+    #[test]
+    fn extremes_take_ten_bytes() {
+        assert_eq!(encode(&[Number::from_i64(i64::MIN)]).len() - 2, MAX_VARINT_LEN);
+        assert_eq!(encode(&[Number::from_i64(i64::MAX)]).len() - 2, MAX_VARINT_LEN);
+    }
+
+    #[test]
+    fn decode_of_random_bytes_never_panics() {
+        // 절반은 version을 맞춰서 header 뒤쪽까지 들어가게 한다.
+        let gen = (elements(&[false, true]), vec_of(range(0, 255), 32));
+        check("CODEC_SEED", 50_000, gen, |(versioned, bytes)| {
+            let mut bytes: Vec<u8> = bytes.iter().map(|&b| b as u8).collect();
+            if *versioned && !bytes.is_empty() {
+                bytes[0] = VERSION;
+            }
+            let _ = decode(&bytes);
+            let _ = decode_from(bytes.as_slice());
+        });
+    }
+
+    #[test]
+    fn parity_flag_disagreeing_with_value_is_corruption() {
+        let mut encoded = encode(&[Number::Even(2), Number::Odd(7)]);
+        // 두 번째 값의 parity bit를 뒤집는다.
+        encoded[3] ^= 1;
+        assert!(matches!(decode(&encoded), Err(CodecError::ParityMismatch { index: 1 })));
+    }
+
+    #[test]
+    fn mislabeled_variants_are_encoded_by_their_value() {
+        let encoded = encode(&[Number::Odd(2), Number::Even(3), Number::Odd(i64::MIN)]);
+        let decoded = decode(&encoded).unwrap();
+        assert!(matches!(decoded[..], [Number::Even(2), Number::Odd(3), Number::Even(i64::MIN)]));
+        assert_eq!(encoded, encode(&[Number::Even(2), Number::Odd(3), Number::Even(i64::MIN)]));
+    }
+
+    #[test]
+    fn rejects_non_canonical_varints() {
+        // count = 1 을 0x81 0x00 으로 길게 쓴 경우
+        assert!(matches!(decode(&[VERSION, 0x81, 0x00, 0x00]), Err(CodecError::NonCanonicalVarint)));
+        // 값 0 을 0x80 0x00 으로 길게 쓴 경우
+        assert!(matches!(decode(&[VERSION, 0x01, 0x80, 0x00]), Err(CodecError::NonCanonicalVarint)));
+    }
+
+    #[test]
+    fn rejects_varints_longer_than_65_bits() {
+        let mut bytes = vec![VERSION, 0x01];
+        bytes.extend([0xff; MAX_VARINT_LEN - 1]);
+        bytes.push(0x04);
+        assert!(matches!(decode(&bytes), Err(CodecError::VarintOverflow)));
+    }
+
+    #[test]
+    fn rejects_bad_headers_truncation_and_trailing_bytes() {
+        assert!(matches!(decode(&[]), Err(CodecError::UnexpectedEof)));
+        assert!(matches!(decode(&[2, 0]), Err(CodecError::UnsupportedVersion(2))));
+        // count는 2인데 값이 하나뿐이다.
+        assert!(matches!(decode(&[VERSION, 0x02, 0x00]), Err(CodecError::UnexpectedEof)));
+
+        let mut encoded = encode(&[Number::Odd(3)]);
+        encoded.push(0);
+        assert!(matches!(decode(&encoded), Err(CodecError::TrailingBytes(1))));
+    }
+
+    #[test]
+    fn streaming_round_trip_stops_after_one_message() {
+        let numbers = [Number::Odd(-3), Number::Even(10)];
+        let mut buf = Vec::new();
+        encode_to(&numbers, &mut buf).unwrap();
+        encode_to(&[Number::Even(0)], &mut buf).unwrap();
+
+        let mut reader = buf.as_slice();
+        assert_eq!(values(&decode_from(&mut reader).unwrap()), vec![-3, 10]);
+        assert_eq!(values(&decode_from(&mut reader).unwrap()), vec![0]);
+        assert!(reader.is_empty());
+    }
+}
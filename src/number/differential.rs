@@ -0,0 +1,131 @@
+use std::num::IntErrorKind;
+
+use super::eval::{eval, EvalError};
+use crate::proptest_lite::{any_i64, boxed, check, elements, one_of, range, string_of, vec_of};
+
+/*
+    eval의 10진수 literal 경로와 i64::from_str가 같은 걸 정수로 보는지 비교한다.
+
+    eval은 수식을 받으니까 from_str보다 받아주는 게 많은 건 당연하다. (" 5 ", "--5", "1-2", "0x10")
+    그래서 규칙은 이렇다.
+
+    1. from_str가 받는 건 eval도 받고, 값도 같아야 한다.
+    2. `[+-]?digits` 모양인데 from_str가 overflow라고 하면 eval도 Overflow여야 한다.
+       (from_str는 앞에서부터 읽다가 넘치면 바로 overflow라서 "9223372036854775808.0"도 overflow다.
+       그래서 모양이 맞을 때만 비교한다.)
+    3. from_str가 거절하는데 eval이 받는 건, 입력이 `[+-]?digits` 모양이 아닐 때뿐이다.
+       공백, 부호 여러 개, 연산자, 0x 같은 수식 문법 때문에 받은 경우다.
+
+    cargo test에서는 정해진 개수만 돈다. fuzz feature를 켜고 DIFFERENTIAL_CASES를 주면 그만큼 돈다.
+    (DIFFERENTIAL_CASES=max면 사실상 끝없이 돈다.) --all-features만 켜서는 개수가 그대로다.
+    실패하면 proptest_lite가 반례를 줄여서 seed와 같이 찍어준다. (DIFFERENTIAL_SEED=0x...)
+    여기서 찾은 차이는 아래 regression test로 박아둔다.
+*/
+
+const CASES: u32 = 20_000;
+
+#[cfg(not(feature = "fuzz"))]
+fn cases() -> u32 {
+    CASES
+}
+
+#[cfg(feature = "fuzz")]
+fn cases() -> u32 {
+    match std::env::var("DIFFERENTIAL_CASES") {
+        Ok(text) if text == "max" => u32::MAX,
+        Ok(text) => text.parse().unwrap_or_else(|_| panic!("DIFFERENTIAL_CASES={text:?} is not a u32 or \"max\"")),
+        Err(_) => CASES,
+    }
+}
+
+// 아무 문자열이나 넣어보는 쪽의 charset. 숫자와, 숫자 근처에서 헷갈릴 만한 것들.
+const NOISE: &str = "0123456789+-_ \t0x.";
+
+// 멀쩡한 literal에 끼워 넣을 것들.
+const NEAR_MISSES: [&str; 8] = ["+", "-", "_", " ", "\t", "0", "9", ".0"];
+
+const BOUNDARIES: [i64; 6] = [i64::MIN, i64::MAX, i64::MIN + 1, i64::MAX - 1, 0, -1];
+
+fn is_plain_literal(text: &str) -> bool {
+    let digits = text.strip_prefix(['+', '-']).unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn agree(text: &str) {
+    let ours = eval(text).map(|number| number.value());
+    match text.parse::<i64>() {
+        Ok(value) => assert_eq!(ours, Ok(value), "std accepts {text:?}"),
+        Err(e) if is_plain_literal(text) && matches!(e.kind(), IntErrorKind::PosOverflow | IntErrorKind::NegOverflow) => {
+            assert!(matches!(ours, Err(EvalError::Overflow { .. })), "std overflows on {text:?} but eval gave {ours:?}");
+        }
+        Err(_) => {
+            if let Ok(value) = ours {
+                assert!(!is_plain_literal(text), "std rejects {text:?} but eval gave {value}");
+            }
+        }
+    }
+}
+
+#[test]
+fn random_strings_agree_with_std() {
+    check("DIFFERENTIAL_SEED", cases(), string_of(NOISE, 24), |text| agree(text));
+}
+
+// 진짜 i64 literal 하나에 near-miss 조각을 몇 개 끼워 넣는다. 숫자를 하나 더 붙이면 overflow 근처가 된다.
+#[test]
+fn mutated_literals_agree_with_std() {
+    let value = one_of(vec![boxed(any_i64()), boxed(elements(&BOUNDARIES))]);
+    let edits = vec_of((range(0, 21), elements(&NEAR_MISSES)), 3);
+    check("DIFFERENTIAL_SEED", cases(), (value, edits), |(value, edits)| {
+        let mut text = value.to_string();
+        for &(at, piece) in edits {
+            let at = (at as usize).min(text.len());
+            text.insert_str(at, piece);
+        }
+        agree(&text);
+    });
+}
+
+/*
+    ==== 찾은 차이들 ====
+*/
+
+// 처음 돌렸을 때 바로 "+0"으로 줄어든 반례가 나왔다. eval에 단항 '+'가 없었다.
+#[test]
+fn leading_plus_is_accepted_like_std() {
+    for text in ["+0", "+5", "+9223372036854775807", "+007"] {
+        assert_eq!(eval(text).map(|n| n.value()).ok(), text.parse::<i64>().ok(), "{text}");
+    }
+    assert!(matches!(eval("+9223372036854775808"), Err(EvalError::Overflow { .. })));
+    // std는 부호를 하나만 받는다. eval에서는 단항 연산자 두 개라서 받는다. (규칙 3)
+    assert!("+-5".parse::<i64>().is_err());
+    assert_eq!(eval("+-5").unwrap().value(), -5);
+}
+
+// u64에 안 들어가는 literal이 InvalidLiteral로 나왔었다. (규칙 2)
+#[test]
+fn overflow_is_typed_at_every_size() {
+    for text in ["9223372036854775808", "18446744073709551616", "-18446744073709551616", "99999999999999999999999"] {
+        assert!(text.parse::<i64>().is_err());
+        assert!(matches!(eval(text), Err(EvalError::Overflow { .. })), "{text}");
+    }
+}
+
+// 공백은 eval에서 token 사이를 나누는 문자라서 일부러 다르게 둔다.
+// 대신 literal 안쪽을 쪼개는 공백은 받아주지 않는다.
+#[test]
+fn whitespace_is_a_separator_not_part_of_the_literal() {
+    assert!(" 5 ".parse::<i64>().is_err());
+    assert_eq!(eval(" 5 ").unwrap().value(), 5);
+    assert_eq!(eval("- 5").unwrap().value(), -5);
+    assert!(matches!(eval("5 5"), Err(EvalError::UnexpectedToken { .. })));
+}
+
+// 1_000 같은 구분자는 둘 다 안 받는다.
+#[test]
+fn underscores_are_rejected_by_both() {
+    for text in ["1_000", "_1", "1_"] {
+        assert!(text.parse::<i64>().is_err());
+        assert!(eval(text).is_err(), "{text}");
+    }
+}
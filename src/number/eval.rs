@@ -0,0 +1,671 @@
+use std::{error::Error, fmt};
+
+use crate::Number;
+
+/*
+    Number 연습의 마무리로 만든 작은 정수 계산기.
+
+    eval("gcd(0x18, 0b1010) * -(3 + 4) % 5")
+
+    문자열 -> lexer -> Token들 -> parser -> Expr(AST) -> 계산 -> Number
+    계산은 전부 checked 연산이라 overflow나 0으로 나누기는 panic이 아니라 EvalError가 된다.
+    에러에는 문제가 된 연산자의 위치(Span)가 같이 들어간다.
+
+    문법 (위로 갈수록 우선순위가 낮다. 이항 연산자는 전부 왼쪽 결합)
+
+    expr    := term (('+' | '-') term)*
+    term    := unary (('*' | '/' | '%') unary)*
+    unary   := ('-' | '+') unary | primary
+    primary := INT | IDENT '(' args ')' | '(' expr ')'
+    args    := expr (',' expr)*
+*/
+
+// 괄호나 단항 minus가 이보다 깊으면 stack overflow 대신 에러를 돌려준다.
+const MAX_DEPTH: usize = 128;
+
+// 입력 문자열 안의 byte 위치. [start, end)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvalError {
+    InvalidCharacter { ch: char, span: Span },
+    InvalidLiteral { span: Span },
+    UnexpectedToken { found: String, span: Span },
+    UnexpectedEnd { span: Span },
+    TooDeep { span: Span },
+    UnknownFunction { name: String, span: Span },
+    WrongArity { name: String, expected: usize, found: usize, span: Span },
+    InvalidArgument { name: String, span: Span },
+    Overflow { span: Span },
+    DivisionByZero { span: Span },
+}
+
+impl EvalError {
+    pub fn span(&self) -> Span {
+        match self {
+            EvalError::InvalidCharacter { span, .. }
+            | EvalError::InvalidLiteral { span }
+            | EvalError::UnexpectedToken { span, .. }
+            | EvalError::UnexpectedEnd { span }
+            | EvalError::TooDeep { span }
+            | EvalError::UnknownFunction { span, .. }
+            | EvalError::WrongArity { span, .. }
+            | EvalError::InvalidArgument { span, .. }
+            | EvalError::Overflow { span }
+            | EvalError::DivisionByZero { span } => *span,
+        }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::InvalidCharacter { ch, span } => write!(f, "Invalid character {ch:?} at {span}"),
+            EvalError::InvalidLiteral { span } => write!(f, "Invalid integer literal at {span}"),
+            EvalError::UnexpectedToken { found, span } => write!(f, "Unexpected {found:?} at {span}"),
+            EvalError::UnexpectedEnd { span } => write!(f, "Unexpected end of input at {span}"),
+            EvalError::TooDeep { span } => write!(f, "Expression nested deeper than {MAX_DEPTH} at {span}"),
+            EvalError::UnknownFunction { name, span } => write!(f, "Unknown function {name:?} at {span}"),
+            EvalError::WrongArity { name, expected, found, span } => {
+                write!(f, "{name} takes {expected} argument(s) but got {found} at {span}")
+            }
+            EvalError::InvalidArgument { name, span } => write!(f, "Invalid argument to {name} at {span}"),
+            EvalError::Overflow { span } => write!(f, "Overflow at {span}"),
+            EvalError::DivisionByZero { span } => write!(f, "Division by zero at {span}"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+pub fn eval(expr: &str) -> Result<Number, EvalError> {
+    let tokens = lex(expr)?;
+    let ast = Parser { tokens: &tokens, pos: 0, input_len: expr.len() }.parse()?;
+    Ok(Number::from_i64(ast.eval()?))
+}
+
+/*
+    ==== Lexer ====
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    // i64::MIN의 절댓값은 i64에 안 들어가서 literal은 u64로 들고 있다가 parser에서 부호를 붙인다.
+    Int(u64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    LParen,
+    RParen,
+    Comma,
+}
+
+#[derive(Debug)]
+struct Token {
+    kind: TokenKind,
+    span: Span,
+}
+
+fn lex(input: &str) -> Result<Vec<Token>, EvalError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(ch) = input[pos..].chars().next() {
+        let start = pos;
+        let single = match ch {
+            '+' => Some(TokenKind::Plus),
+            '-' => Some(TokenKind::Minus),
+            '*' => Some(TokenKind::Star),
+            '/' => Some(TokenKind::Slash),
+            '%' => Some(TokenKind::Percent),
+            '(' => Some(TokenKind::LParen),
+            ')' => Some(TokenKind::RParen),
+            ',' => Some(TokenKind::Comma),
+            _ => None,
+        };
+
+        if let Some(kind) = single {
+            pos += 1;
+            tokens.push(Token { kind, span: Span { start, end: pos } });
+        } else if ch.is_ascii_whitespace() {
+            pos += 1;
+        } else if ch.is_ascii_digit() {
+            // 0x1F 처럼 숫자 뒤에 붙은 알파벳까지 한 덩어리로 읽고 나서 해석한다.
+            while pos < bytes.len() && bytes[pos].is_ascii_alphanumeric() {
+                pos += 1;
+            }
+            let span = Span { start, end: pos };
+            let value = parse_literal(&input[start..pos], span)?;
+            tokens.push(Token { kind: TokenKind::Int(value), span });
+        } else if ch.is_ascii_alphabetic() || ch == '_' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            let span = Span { start, end: pos };
+            tokens.push(Token { kind: TokenKind::Ident(input[start..pos].to_string()), span });
+        } else {
+            let span = Span { start, end: start + ch.len_utf8() };
+            return Err(EvalError::InvalidCharacter { ch, span });
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_literal(text: &str, span: Span) -> Result<u64, EvalError> {
+    let (digits, radix) = match text.get(..2) {
+        Some("0x") | Some("0X") => (&text[2..], 16),
+        Some("0o") | Some("0O") => (&text[2..], 8),
+        Some("0b") | Some("0B") => (&text[2..], 2),
+        _ => (text, 10),
+    };
+    // from_str_radix는 앞의 '+'를 받아주니까 숫자만 있는지 먼저 확인한다.
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(EvalError::InvalidLiteral { span });
+    }
+    // 숫자만 남았으니 실패하는 건 u64에 안 들어가는 경우뿐이다.
+    u64::from_str_radix(digits, radix).map_err(|_| EvalError::Overflow { span })
+}
+
+/*
+    ==== Parser ====
+*/
+
+#[derive(Debug, Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Func {
+    Gcd,
+    Abs,
+    Factorial,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gcd" => Some(Func::Gcd),
+            "abs" => Some(Func::Abs),
+            "factorial" => Some(Func::Factorial),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Func::Gcd => "gcd",
+            Func::Abs => "abs",
+            Func::Factorial => "factorial",
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Func::Gcd => 2,
+            Func::Abs | Func::Factorial => 1,
+        }
+    }
+}
+
+// 각 노드의 span은 에러가 났을 때 가리킬 곳이다. (연산자, 함수 이름)
+//
+// 1 + 2 + 3 + ... 을 Binary(Binary(Binary(..)))로 만들면 항 개수만큼 깊어져서
+// 계산이나 Drop이 재귀로 내려가다 stack overflow가 난다.
+// 그래서 같은 우선순위의 왼쪽 결합 chain은 tree 대신 Vec로 펴서 들고 있는다.
+// 이러면 tree 깊이는 괄호/단항 minus/함수 호출 중첩(MAX_DEPTH로 제한)에만 비례한다.
+#[derive(Debug)]
+enum Expr {
+    Literal(i64),
+    Neg { operand: Box<Expr>, span: Span },
+    Chain { first: Box<Expr>, rest: Vec<(BinaryOp, Span, Expr)> },
+    Call { func: Func, args: Vec<Expr>, span: Span },
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    input_len: usize,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<Expr, EvalError> {
+        let expr = self.expr(0)?;
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some(token) => Err(unexpected(token)),
+        }
+    }
+
+    fn expr(&mut self, depth: usize) -> Result<Expr, EvalError> {
+        let first = self.term(depth)?;
+        let mut rest = Vec::new();
+        while let Some((op, span)) = self.binary_op(&[TokenKind::Plus, TokenKind::Minus]) {
+            rest.push((op, span, self.term(depth)?));
+        }
+        Ok(chain(first, rest))
+    }
+
+    fn term(&mut self, depth: usize) -> Result<Expr, EvalError> {
+        let first = self.unary(depth)?;
+        let mut rest = Vec::new();
+        while let Some((op, span)) = self.binary_op(&[TokenKind::Star, TokenKind::Slash, TokenKind::Percent]) {
+            rest.push((op, span, self.unary(depth)?));
+        }
+        Ok(chain(first, rest))
+    }
+
+    fn unary(&mut self, depth: usize) -> Result<Expr, EvalError> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            return Err(self.unexpected_end());
+        };
+        if token.kind == TokenKind::Plus {
+            // i64::from_str가 "+5"를 받아주니까 여기서도 받는다. 값은 그대로다.
+            let depth = self.descend(depth, token.span)?;
+            self.pos += 1;
+            return self.unary(depth);
+        }
+        if token.kind != TokenKind::Minus {
+            return self.primary(depth);
+        }
+
+        let span = token.span;
+        let depth = self.descend(depth, span)?;
+        self.pos += 1;
+
+        // -9223372036854775808 가 i64::MIN이 되도록 literal 바로 앞의 minus는 여기서 접는다.
+        // 단항 minus가 모든 이항 연산자보다 강하게 묶이니까 접어도 의미가 같다.
+        if let Some(Token { kind: TokenKind::Int(value), span: lit_span }) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            let value = 0i64
+                .checked_sub_unsigned(*value)
+                .ok_or(EvalError::Overflow { span: Span { start: span.start, end: lit_span.end } })?;
+            return Ok(Expr::Literal(value));
+        }
+
+        let operand = self.unary(depth)?;
+        Ok(Expr::Neg { operand: Box::new(operand), span })
+    }
+
+    fn primary(&mut self, depth: usize) -> Result<Expr, EvalError> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            return Err(self.unexpected_end());
+        };
+        self.pos += 1;
+
+        match &token.kind {
+            TokenKind::Int(value) => {
+                let value = i64::try_from(*value).map_err(|_| EvalError::Overflow { span: token.span })?;
+                Ok(Expr::Literal(value))
+            }
+            TokenKind::LParen => {
+                let depth = self.descend(depth, token.span)?;
+                let expr = self.expr(depth)?;
+                self.expect(TokenKind::RParen)?;
+                Ok(expr)
+            }
+            TokenKind::Ident(name) => {
+                let span = token.span;
+                let func = Func::from_name(name)
+                    .ok_or_else(|| EvalError::UnknownFunction { name: name.clone(), span })?;
+                let depth = self.descend(depth, span)?;
+                self.expect(TokenKind::LParen)?;
+
+                // gcd() 처럼 인자가 없으면 바로 ')'가 온다. 문법 에러가 아니라 arity 에러로 보낸다.
+                let mut args = Vec::new();
+                if !self.eat(&TokenKind::RParen) {
+                    args.push(self.expr(depth)?);
+                    while self.eat(&TokenKind::Comma) {
+                        args.push(self.expr(depth)?);
+                    }
+                    self.expect(TokenKind::RParen)?;
+                }
+
+                if args.len() != func.arity() {
+                    return Err(EvalError::WrongArity {
+                        name: func.name().to_string(),
+                        expected: func.arity(),
+                        found: args.len(),
+                        span,
+                    });
+                }
+                Ok(Expr::Call { func, args, span })
+            }
+            _ => Err(unexpected(token)),
+        }
+    }
+
+    fn binary_op(&mut self, allowed: &[TokenKind]) -> Option<(BinaryOp, Span)> {
+        let token = self.tokens.get(self.pos)?;
+        if !allowed.contains(&token.kind) {
+            return None;
+        }
+        let op = match token.kind {
+            TokenKind::Plus => BinaryOp::Add,
+            TokenKind::Minus => BinaryOp::Sub,
+            TokenKind::Star => BinaryOp::Mul,
+            TokenKind::Slash => BinaryOp::Div,
+            TokenKind::Percent => BinaryOp::Rem,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some((op, token.span))
+    }
+
+    fn descend(&self, depth: usize, span: Span) -> Result<usize, EvalError> {
+        match depth < MAX_DEPTH {
+            true => Ok(depth + 1),
+            false => Err(EvalError::TooDeep { span }),
+        }
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(token) if &token.kind == kind => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<(), EvalError> {
+        match self.tokens.get(self.pos) {
+            Some(token) if token.kind == kind => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => Err(unexpected(token)),
+            None => Err(self.unexpected_end()),
+        }
+    }
+
+    fn unexpected_end(&self) -> EvalError {
+        EvalError::UnexpectedEnd { span: Span { start: self.input_len, end: self.input_len } }
+    }
+}
+
+fn chain(first: Expr, rest: Vec<(BinaryOp, Span, Expr)>) -> Expr {
+    match rest.is_empty() {
+        true => first,
+        false => Expr::Chain { first: Box::new(first), rest },
+    }
+}
+
+fn unexpected(token: &Token) -> EvalError {
+    let found = match &token.kind {
+        TokenKind::Int(value) => value.to_string(),
+        TokenKind::Ident(name) => name.clone(),
+        TokenKind::Plus => "+".to_string(),
+        TokenKind::Minus => "-".to_string(),
+        TokenKind::Star => "*".to_string(),
+        TokenKind::Slash => "/".to_string(),
+        TokenKind::Percent => "%".to_string(),
+        TokenKind::LParen => "(".to_string(),
+        TokenKind::RParen => ")".to_string(),
+        TokenKind::Comma => ",".to_string(),
+    };
+    EvalError::UnexpectedToken { found, span: token.span }
+}
+
+/*
+    ==== Eval ====
+    Chain은 반복문으로 접고, 나머지 중첩은 parser가 MAX_DEPTH로 막아두었으니
+    여기는 재귀로 내려가도 된다.
+*/
+
+impl Expr {
+    fn eval(&self) -> Result<i64, EvalError> {
+        match self {
+            Expr::Literal(value) => Ok(*value),
+            Expr::Neg { operand, span } => operand.eval()?.checked_neg().ok_or(EvalError::Overflow { span: *span }),
+            Expr::Chain { first, rest } => {
+                let mut acc = first.eval()?;
+                for (op, span, rhs) in rest {
+                    acc = apply(*op, acc, rhs.eval()?, *span)?;
+                }
+                Ok(acc)
+            }
+            Expr::Call { func, args, span } => {
+                let args = args.iter().map(Expr::eval).collect::<Result<Vec<_>, _>>()?;
+                let overflow = EvalError::Overflow { span: *span };
+                match func {
+                    Func::Gcd => {
+                        let (mut a, mut b) = (args[0].unsigned_abs(), args[1].unsigned_abs());
+                        while b != 0 {
+                            (a, b) = (b, a % b);
+                        }
+                        i64::try_from(a).map_err(|_| overflow)
+                    }
+                    Func::Abs => args[0].checked_abs().ok_or(overflow),
+                    Func::Factorial => {
+                        if args[0] < 0 {
+                            return Err(EvalError::InvalidArgument { name: func.name().to_string(), span: *span });
+                        }
+                        (2..=args[0]).try_fold(1i64, |acc, n| acc.checked_mul(n)).ok_or(overflow)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn apply(op: BinaryOp, lhs: i64, rhs: i64, span: Span) -> Result<i64, EvalError> {
+    let overflow = EvalError::Overflow { span };
+    match op {
+        BinaryOp::Add => lhs.checked_add(rhs).ok_or(overflow),
+        BinaryOp::Sub => lhs.checked_sub(rhs).ok_or(overflow),
+        BinaryOp::Mul => lhs.checked_mul(rhs).ok_or(overflow),
+        BinaryOp::Div | BinaryOp::Rem if rhs == 0 => Err(EvalError::DivisionByZero { span }),
+        // i64::MIN / -1 도 여기서 걸린다.
+        BinaryOp::Div => lhs.checked_div(rhs).ok_or(overflow),
+        // i64::MIN % -1 은 0이라서 넘치지 않는다. checked_rem은 이것도 None으로 준다.
+        BinaryOp::Rem => Ok(lhs.wrapping_rem(rhs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proptest_lite::{check, elements, range};
+
+    fn value(expr: &str) -> i64 {
+        match eval(expr) {
+            Ok(number) => number.value(),
+            Err(e) => panic!("{expr:?} failed: {e}"),
+        }
+    }
+
+    fn error(expr: &str) -> EvalError {
+        match eval(expr) {
+            Ok(number) => panic!("{expr:?} evaluated to {}", number.value()),
+            Err(e) => e,
+        }
+    }
+
+    fn span(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        let table = [
+            ("1 + 2 * 3", 7),
+            ("(1 + 2) * 3", 9),
+            ("10 - 3 - 2", 5),
+            ("10 - (3 - 2)", 9),
+            ("100 / 10 / 2", 5),
+            ("100 / (10 / 2)", 20),
+            ("7 % 4 * 2", 6),
+            ("2 * 7 % 4", 2),
+            ("1 + 2 * 3 - 4 / 2 % 3", 5),
+            ("-2 * 3", -6),
+            ("2 * -3", -6),
+            ("-2 - -3", 1),
+            ("--3", 3),
+            ("-(3 + 4)", -7),
+            ("+3", 3),
+            ("2 * +3", 6),
+            ("-+3", -3),
+            ("-7 / 2", -3),
+            ("-7 % 2", -1),
+            ("gcd(12, 18) + abs(-5) * factorial(3)", 36),
+        ];
+        for (expr, expected) in table {
+            assert_eq!(value(expr), expected, "{expr}");
+        }
+    }
+
+    #[test]
+    fn literals_in_every_radix() {
+        assert_eq!(value("0x1F"), 31);
+        assert_eq!(value("0XfF"), 255);
+        assert_eq!(value("0o17"), 15);
+        assert_eq!(value("0b1010"), 10);
+        assert_eq!(value("0x18 + 0b1010 + 0o7 + 9"), 50);
+        assert!(matches!(error("0x"), EvalError::InvalidLiteral { .. }));
+        assert!(matches!(error("0b102"), EvalError::InvalidLiteral { .. }));
+        assert!(matches!(error("12abc"), EvalError::InvalidLiteral { .. }));
+    }
+
+    #[test]
+    fn results_keep_their_parity() {
+        assert!(matches!(eval("3 * 5"), Ok(Number::Odd(15))));
+        assert!(matches!(eval("3 * 4"), Ok(Number::Even(12))));
+
+        // 곱이 짝수인 건 둘 중 하나라도 짝수일 때, 합/차가 짝수인 건 parity가 같을 때뿐이다.
+        let gen = ((range(-1_000_000, 1_000_000), range(-1_000_000, 1_000_000)), elements(&['+', '-', '*']));
+        check("EVAL_SEED", 1000, gen, |&((a, b), op)| {
+            let expected_even = match op {
+                '*' => a % 2 == 0 || b % 2 == 0,
+                _ => (a % 2 == 0) == (b % 2 == 0),
+            };
+            match eval(&format!("{a} {op} {b}")) {
+                Ok(Number::Even(_)) => assert!(expected_even, "{a} {op} {b} should be odd"),
+                Ok(Number::Odd(_)) => assert!(!expected_even, "{a} {op} {b} should be even"),
+                Err(e) => panic!("{a} {op} {b} failed: {e}"),
+            }
+        });
+    }
+
+    #[test]
+    fn i64_extremes() {
+        assert_eq!(value("9223372036854775807"), i64::MAX);
+        assert_eq!(value("-9223372036854775808"), i64::MIN);
+        assert_eq!(error("9223372036854775808"), EvalError::Overflow { span: span(0, 19) });
+        assert_eq!(error("-9223372036854775809"), EvalError::Overflow { span: span(0, 20) });
+        // u64에도 안 들어가는 literal도 InvalidLiteral이 아니라 Overflow다.
+        assert_eq!(error("18446744073709551616"), EvalError::Overflow { span: span(0, 20) });
+        assert_eq!(error("-18446744073709551616"), EvalError::Overflow { span: span(1, 21) });
+        assert_eq!(error("0xFFFFFFFFFFFFFFFFF"), EvalError::Overflow { span: span(0, 19) });
+        assert_eq!(error("1 + 0b12"), EvalError::InvalidLiteral { span: span(4, 8) });
+    }
+
+    #[test]
+    fn arithmetic_errors_point_at_the_operator() {
+        assert_eq!(error("1 / 0"), EvalError::DivisionByZero { span: span(2, 3) });
+        assert_eq!(error("1 + 5 % (2 - 2)"), EvalError::DivisionByZero { span: span(6, 7) });
+        assert_eq!(error("9223372036854775807 + 1"), EvalError::Overflow { span: span(20, 21) });
+        assert_eq!(error("1 + 2 * 9223372036854775807"), EvalError::Overflow { span: span(6, 7) });
+        assert_eq!(error("-9223372036854775808 / -1"), EvalError::Overflow { span: span(21, 22) });
+        assert_eq!(error("-(-9223372036854775808)"), EvalError::Overflow { span: span(0, 1) });
+        assert_eq!(error("-9223372036854775808 / -1").to_string(), "Overflow at 21..22");
+        assert_eq!(value("-9223372036854775808 % -1"), 0);
+    }
+
+    #[test]
+    fn factorial_overflows_at_21() {
+        assert_eq!(value("factorial(20)"), 2_432_902_008_176_640_000);
+        assert_eq!(error("factorial(21)"), EvalError::Overflow { span: span(0, 9) });
+        assert_eq!(error("1 + factorial(21)"), EvalError::Overflow { span: span(4, 13) });
+        assert_eq!(value("factorial(0)"), 1);
+        assert!(matches!(error("factorial(-1)"), EvalError::InvalidArgument { .. }));
+    }
+
+    #[test]
+    fn function_errors() {
+        assert_eq!(value("gcd(0, 0)"), 0);
+        assert_eq!(value("gcd(-12, 18)"), 6);
+        assert_eq!(error("gcd(-9223372036854775808, 0)"), EvalError::Overflow { span: span(0, 3) });
+        assert_eq!(error("abs(-9223372036854775808)"), EvalError::Overflow { span: span(0, 3) });
+        assert_eq!(
+            error("foo(1)"),
+            EvalError::UnknownFunction { name: "foo".to_string(), span: span(0, 3) }
+        );
+        assert_eq!(
+            error("1 + gcd(1)"),
+            EvalError::WrongArity { name: "gcd".to_string(), expected: 2, found: 1, span: span(4, 7) }
+        );
+        assert_eq!(
+            error("gcd()"),
+            EvalError::WrongArity { name: "gcd".to_string(), expected: 2, found: 0, span: span(0, 3) }
+        );
+        assert_eq!(
+            error("abs( )"),
+            EvalError::WrongArity { name: "abs".to_string(), expected: 1, found: 0, span: span(0, 3) }
+        );
+        assert_eq!(error("gcd(1,)"), EvalError::UnexpectedToken { found: ")".to_string(), span: span(6, 7) });
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_offending_token() {
+        assert_eq!(error(""), EvalError::UnexpectedEnd { span: span(0, 0) });
+        assert_eq!(error("1 +"), EvalError::UnexpectedEnd { span: span(3, 3) });
+        assert_eq!(error("(1 + 2"), EvalError::UnexpectedEnd { span: span(6, 6) });
+        assert_eq!(error("1 2"), EvalError::UnexpectedToken { found: "2".to_string(), span: span(2, 3) });
+        assert_eq!(error("1 + )"), EvalError::UnexpectedToken { found: ")".to_string(), span: span(4, 5) });
+        assert_eq!(error("1 # 2"), EvalError::InvalidCharacter { ch: '#', span: span(2, 3) });
+        // 멀티바이트 문자는 byte 단위 span을 가진다.
+        assert_eq!(error("1 + 한"), EvalError::InvalidCharacter { ch: '한', span: span(4, 7) });
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_is_fine() {
+        let nested = format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert_eq!(value(&nested), 1);
+        assert_eq!(value(&format!("{}1", "-".repeat(MAX_DEPTH))), 1);
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected_without_overflowing_the_stack() {
+        let parens = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert_eq!(error(&parens), EvalError::TooDeep { span: span(MAX_DEPTH, MAX_DEPTH + 1) });
+
+        let minus = format!("{}1", "-".repeat(100_000));
+        assert!(matches!(error(&minus), EvalError::TooDeep { .. }));
+
+        let plus = format!("{}1", "+".repeat(100_000));
+        assert!(matches!(error(&plus), EvalError::TooDeep { .. }));
+
+        let calls = format!("{}1{}", "abs(".repeat(100_000), ")".repeat(100_000));
+        assert!(matches!(error(&calls), EvalError::TooDeep { .. }));
+    }
+
+    #[test]
+    fn long_flat_chains_do_not_overflow_the_stack() {
+        // tree로 만들면 항 개수만큼 깊어지는 입력들. 평가와 Drop 모두 끝까지 가야 한다.
+        assert_eq!(value(&format!("1{}", "+1".repeat(200_000))), 200_001);
+        assert_eq!(value(&format!("1{}", "*1".repeat(200_000))), 1);
+        assert_eq!(value(&format!("0{}", "-1*1".repeat(100_000))), -100_000);
+        assert_eq!(value(&format!("1{}", "+(1+1)".repeat(50_000))), 100_001);
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+};
+
+/*
+    테스트에서만 쓰는 아주 작은 property test 도구.
+
+    check("CODEC_SEED", 500, vec_of(any_i64(), 64), |values| { ... assert!(...) ... });
+
+    - Gen이 값을 만들고, 실패하면 shrink로 더 작은 후보들을 내놓는다.
+    - property는 그냥 assert!로 쓴다. panic이 나면 실패다.
+    - 실패하면 shrink를 반복해서 제일 작은 반례와 그 case의 seed를 찍어준다.
+      그 seed를 환경 변수로 주면(CODEC_SEED=0x...) 첫 case에서 바로 같은 실패가 난다.
+
+    seed가 없으면 이름에서 만든 고정 seed를 쓰기 때문에 cargo test는 항상 같은 입력으로 돈다.
+*/
+
+// shrink를 무한히 하지 않게 막는 상한.
+const MAX_SHRINK_STEPS: usize = 4096;
+
+// xorshift64. seed가 같으면 항상 같은 수열이 나온다.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift는 상태가 0이면 계속 0만 나온다.
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // [0, n) 사이의 수. n은 0이면 안된다.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+pub trait Gen {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    // value보다 "작은" 후보들. 앞에 있을수록 많이 줄인 후보다.
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        Vec::new()
+    }
+}
+
+pub type BoxedGen<T> = Box<dyn Gen<Value = T>>;
+
+pub fn boxed<G: Gen + 'static>(gen: G) -> BoxedGen<G::Value> {
+    Box::new(gen)
+}
+
+impl<T: Clone + Debug> Gen for BoxedGen<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        (**self).generate(rng)
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        (**self).shrink(value)
+    }
+}
+
+/*
+    ==== 정수 ====
+    shrink는 0 쪽으로(범위에 0이 없으면 가까운 끝 쪽으로) 이분 탐색한다.
+    v, target이면 후보는 target, v - d/2, v - d/4, ..., v - 1 (d = v - target)
+*/
+
+pub struct IntRange {
+    lo: i64,
+    hi: i64,
+}
+
+pub fn range(lo: i64, hi: i64) -> IntRange {
+    assert!(lo <= hi, "empty range {lo}..={hi}");
+    IntRange { lo, hi }
+}
+
+pub fn any_i64() -> IntRange {
+    range(i64::MIN, i64::MAX)
+}
+
+impl Gen for IntRange {
+    type Value = i64;
+
+    fn generate(&self, rng: &mut Rng) -> i64 {
+        let width = (self.hi as i128 - self.lo as i128) as u128 + 1;
+        let offset = match u64::try_from(width) {
+            Ok(width) => rng.below(width),
+            // i64 전체 범위는 u64에 딱 안 들어가니 그냥 아무 u64나 쓴다.
+            Err(_) => rng.next_u64(),
+        };
+        (self.lo as i128 + offset as i128) as i64
+    }
+
+    fn shrink(&self, &value: &i64) -> Vec<i64> {
+        if value < self.lo || value > self.hi {
+            return Vec::new();
+        }
+        let target = 0i64.clamp(self.lo, self.hi);
+        let mut candidates = Vec::new();
+        let mut distance = value as i128 - target as i128;
+        if distance != 0 {
+            candidates.push(target);
+        }
+        distance /= 2;
+        while distance != 0 {
+            candidates.push((value as i128 - distance) as i64);
+            distance /= 2;
+        }
+        candidates
+    }
+}
+
+/*
+    ==== 고르기 ====
+*/
+
+// 주어진 값 중 하나. 앞에 있는 값일수록 "작다".
+pub struct Elements<T>(Vec<T>);
+
+pub fn elements<T: Clone + Debug + PartialEq>(values: &[T]) -> Elements<T> {
+    assert!(!values.is_empty(), "elements needs at least one value");
+    Elements(values.to_vec())
+}
+
+impl<T: Clone + Debug + PartialEq> Gen for Elements<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self.0[rng.below(self.0.len() as u64) as usize].clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        match self.0.iter().position(|v| v == value) {
+            Some(at) => self.0[..at].to_vec(),
+            None => Vec::new(),
+        }
+    }
+}
+
+// 여러 generator 중 하나를 골라서 만든다.
+// 어느 쪽에서 나온 값인지는 모르니까 shrink는 전부한테 물어본다.
+pub struct OneOf<T>(Vec<BoxedGen<T>>);
+
+pub fn one_of<T>(gens: Vec<BoxedGen<T>>) -> OneOf<T> {
+    assert!(!gens.is_empty(), "one_of needs at least one generator");
+    OneOf(gens)
+}
+
+impl<T: Clone + Debug> Gen for OneOf<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self.0[rng.below(self.0.len() as u64) as usize].generate(rng)
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        self.0.iter().flat_map(|gen| gen.shrink(value)).collect()
+    }
+}
+
+/*
+    ==== 모음 ====
+    vec은 먼저 원소를 빼보고(뒤쪽 절반, 앞쪽 절반, 하나씩), 그 다음 원소를 하나씩 줄인다.
+*/
+
+pub struct VecOf<G> {
+    element: G,
+    max_len: usize,
+}
+
+pub fn vec_of<G: Gen>(element: G, max_len: usize) -> VecOf<G> {
+    VecOf { element, max_len }
+}
+
+impl<G: Gen> Gen for VecOf<G> {
+    type Value = Vec<G::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        let len = rng.below(self.max_len as u64 + 1) as usize;
+        (0..len).map(|_| self.element.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let mut candidates = removals(value);
+        for (i, item) in value.iter().enumerate() {
+            for smaller in self.element.shrink(item) {
+                let mut candidate = value.clone();
+                candidate[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+fn removals<T: Clone>(value: &[T]) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    if value.len() > 1 {
+        let half = value.len() / 2;
+        candidates.push(value[..half].to_vec());
+        candidates.push(value[half..].to_vec());
+    }
+    for i in 0..value.len() {
+        let mut candidate = value.to_vec();
+        candidate.remove(i);
+        candidates.push(candidate);
+    }
+    candidates
+}
+
+// charset 안의 문자로만 만든 문자열. shrink하면 문자를 빼거나 charset의 첫 문자로 바꾼다.
+pub struct StringOf {
+    charset: Vec<char>,
+    max_len: usize,
+}
+
+pub fn string_of(charset: &str, max_len: usize) -> StringOf {
+    assert!(!charset.is_empty(), "string_of needs a non-empty charset");
+    StringOf { charset: charset.chars().collect(), max_len }
+}
+
+impl Gen for StringOf {
+    type Value = String;
+
+    fn generate(&self, rng: &mut Rng) -> String {
+        let len = rng.below(self.max_len as u64 + 1) as usize;
+        (0..len).map(|_| self.charset[rng.below(self.charset.len() as u64) as usize]).collect()
+    }
+
+    fn shrink(&self, value: &String) -> Vec<String> {
+        let chars: Vec<char> = value.chars().collect();
+        let mut candidates: Vec<String> = removals(&chars).into_iter().map(String::from_iter).collect();
+        let simplest = self.charset[0];
+        for (i, &ch) in chars.iter().enumerate() {
+            if ch != simplest {
+                let mut candidate = chars.clone();
+                candidate[i] = simplest;
+                candidates.push(candidate.into_iter().collect());
+            }
+        }
+        candidates
+    }
+}
+
+// 두 generator를 묶는다. shrink는 한쪽씩 줄인다.
+impl<A: Gen, B: Gen> Gen for (A, B) {
+    type Value = (A::Value, B::Value);
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        (self.0.generate(rng), self.1.generate(rng))
+    }
+
+    fn shrink(&self, (a, b): &Self::Value) -> Vec<Self::Value> {
+        let left = self.0.shrink(a).into_iter().map(|a| (a, b.clone()));
+        let right = self.1.shrink(b).into_iter().map(|b| (a.clone(), b));
+        left.chain(right).collect()
+    }
+}
+
+/*
+    ==== 실행 ====
+*/
+
+#[derive(Debug)]
+pub struct Failure<T> {
+    // 이 seed로 다시 돌리면 첫 case에서 실패한다.
+    pub seed: u64,
+    pub original: T,
+    pub counterexample: T,
+    pub shrink_steps: usize,
+    pub message: String,
+}
+
+// seed부터 cases개를 돌려보고, 실패하면 줄인 반례를 돌려준다.
+pub fn run<G: Gen>(seed: u64, cases: u32, gen: &G, property: impl Fn(&G::Value)) -> Result<(), Failure<G::Value>> {
+    for case in 0..cases {
+        let case_seed = case_seed(seed, case);
+        let value = gen.generate(&mut Rng::new(case_seed));
+        if let Err(message) = holds(&property, &value) {
+            let (counterexample, shrink_steps, message) = minimize(gen, &property, value.clone(), message);
+            return Err(Failure { seed: case_seed, original: value, counterexample, shrink_steps, message });
+        }
+    }
+    Ok(())
+}
+
+// 첫 case는 seed를 그대로 써야 Failure.seed로 다시 돌렸을 때 첫 case에서 재현된다.
+// 나머지는 splitmix64로 섞는다. xorshift 수열에서 바로 뽑으면 다음 case가 이전 case를 한 칸 민 값이 된다.
+fn case_seed(seed: u64, case: u32) -> u64 {
+    if case == 0 {
+        seed
+    } else {
+        splitmix64(seed.wrapping_add(case as u64))
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// 환경 변수 seed_env_var가 있으면 그 seed로, 없으면 이름에서 만든 고정 seed로 돌린다.
+pub fn check<G: Gen>(seed_env_var: &str, cases: u32, gen: G, property: impl Fn(&G::Value)) {
+    let seed = match std::env::var(seed_env_var) {
+        Ok(text) => parse_seed(&text).unwrap_or_else(|| panic!("{seed_env_var}={text:?} is not a u64 seed")),
+        Err(_) => default_seed(seed_env_var),
+    };
+    if let Err(failure) = run(seed, cases, &gen, property) {
+        panic!(
+            "property failed: {}\n  counterexample: {:?} (shrunk {} times from {:?})\n  reproduce with: {}={:#x}",
+            failure.message, failure.counterexample, failure.shrink_steps, failure.original, seed_env_var, failure.seed,
+        );
+    }
+}
+
+fn parse_seed(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+// FNV-1a. 이름마다 다른 seed가 나오면 충분하다.
+fn default_seed(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3))
+}
+
+fn holds<T>(property: &impl Fn(&T), value: &T) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| property(value))).map_err(|payload| {
+        match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+            (Some(message), _) => message.to_string(),
+            (_, Some(message)) => message.clone(),
+            _ => "property panicked".to_string(),
+        }
+    })
+}
+
+// 계속 실패하는 더 작은 후보가 없을 때까지 줄인다.
+fn minimize<G: Gen>(
+    gen: &G,
+    property: &impl Fn(&G::Value),
+    mut current: G::Value,
+    mut message: String,
+) -> (G::Value, usize, String) {
+    let mut steps = 0;
+    'shrinking: while steps < MAX_SHRINK_STEPS {
+        for candidate in gen.shrink(&current) {
+            if let Err(candidate_message) = holds(property, &candidate) {
+                current = candidate;
+                message = candidate_message;
+                steps += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (current, steps, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_shrink_to_the_boundary() {
+        let failure = run(7, 1000, &range(0, 1_000_000), |&x| assert!(x < 1000)).unwrap_err();
+        assert_eq!(failure.counterexample, 1000);
+
+        let failure = run(7, 1000, &any_i64(), |&x| assert!(x > -50)).unwrap_err();
+        assert_eq!(failure.counterexample, -50);
+
+        // 0이 범위 밖이면 가까운 끝으로 줄인다.
+        let failure = run(7, 1000, &range(-900, -100), |&x| assert!(x > -300)).unwrap_err();
+        assert_eq!(failure.counterexample, -300);
+    }
+
+    #[test]
+    fn vectors_shrink_by_removing_then_shrinking_elements() {
+        let gen = vec_of(range(0, 1000), 20);
+        let failure = run(11, 1000, &gen, |v: &Vec<i64>| assert!(v.iter().sum::<i64>() < 100)).unwrap_err();
+        assert_eq!(failure.counterexample, vec![100]);
+
+        let failure = run(11, 1000, &gen, |v: &Vec<i64>| assert!(v.len() < 3)).unwrap_err();
+        assert_eq!(failure.counterexample, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn strings_shrink_toward_the_first_char() {
+        let gen = string_of("ab+", 16);
+        let failure = run(3, 1000, &gen, |s: &String| assert!(!s.contains('+'))).unwrap_err();
+        assert_eq!(failure.counterexample, "+");
+        assert!(failure.message.contains("assertion failed"));
+    }
+
+    #[test]
+    fn one_of_and_pairs_shrink_every_side() {
+        let gen = (one_of(vec![boxed(range(0, 10)), boxed(elements(&[100, 200, 300]))]), range(0, 50));
+        let failure = run(5, 1000, &gen, |&(a, _)| assert!(a < 200)).unwrap_err();
+        assert_eq!(failure.counterexample, (200, 0));
+    }
+
+    #[test]
+    fn failing_seed_reproduces_on_the_first_case() {
+        let gen = range(0, 1_000_000);
+        let failure = run(42, 1000, &gen, |&x| assert!(x % 100 != 7)).unwrap_err();
+        assert_eq!(failure.counterexample % 100, 7);
+
+        let again = run(failure.seed, 1, &gen, |&x| assert!(x % 100 != 7)).unwrap_err();
+        assert_eq!(again.original, failure.original);
+    }
+
+    // rng에서 뽑은 날것의 u64 몇 개. case끼리 수열이 겹치는지 보려고 쓴다.
+    struct Draws;
+
+    impl Gen for Draws {
+        type Value = [u64; 4];
+
+        fn generate(&self, rng: &mut Rng) -> [u64; 4] {
+            [rng.next_u64(), rng.next_u64(), rng.next_u64(), rng.next_u64()]
+        }
+    }
+
+    #[test]
+    fn consecutive_cases_do_not_share_a_shifted_stream() {
+        let cases = std::cell::RefCell::new(Vec::new());
+        run(42, 200, &Draws, |draws| cases.borrow_mut().push(*draws)).unwrap();
+        for pair in cases.borrow().windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            assert_ne!(prev[1..], next[..3], "case is the previous case shifted by one draw");
+            assert!(!prev.contains(&next[0]), "{next:?} starts inside {prev:?}");
+        }
+    }
+
+    #[test]
+    fn passing_properties_run_every_case_quietly() {
+        let runs = std::cell::Cell::new(0);
+        assert!(run(1, 300, &range(-5, 5), |&x| {
+            runs.set(runs.get() + 1);
+            assert!(x.abs() <= 5);
+        })
+        .is_ok());
+        assert_eq!(runs.get(), 300);
+        check("PROPTEST_LITE_SEED", 100, vec_of(any_i64(), 8), |v| assert!(v.len() <= 8));
+    }
+
+    #[test]
+    #[should_panic(expected = "reproduce with: PROPTEST_LITE_SEED=0x")]
+    fn check_reports_the_seed() {
+        check("PROPTEST_LITE_SEED", 100, range(0, 100), |&x| assert!(x < 50));
+    }
+
+    #[test]
+    fn seeds_parse_as_hex_or_decimal() {
+        assert_eq!(parse_seed("0x2a"), Some(42));
+        assert_eq!(parse_seed("42"), Some(42));
+        assert_eq!(parse_seed("forty-two"), None);
+        assert_ne!(default_seed("A_SEED"), default_seed("B_SEED"));
+    }
+}
@@ -0,0 +1,89 @@
+use axum::{
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+
+use crate::MyError;
+
+/*
+    handler에서 Result<Json<T>, MyError>를 매번 쓰는 대신,
+    반환 타입만 보고도 어떤 응답인지 알 수 있게 감싸는 타입들.
+
+    async fn create_user(...) -> AppResult<Created<Json<UserResponse>>> { ... }
+
+    status code와 header는 타입이 정한다. handler가 직접 만들지 않는다.
+*/
+
+pub type AppResult<T> = Result<T, MyError>;
+
+// 200 OK. 안에 든 T가 body를 만든다. (Json, String, ...)
+pub struct Ok200<T>(pub T);
+
+impl<T: IntoResponse> IntoResponse for Ok200<T> {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, self.0).into_response()
+    }
+}
+
+// 201 Created. Location header 없이는 만들 수 없다.
+pub struct Created<T> {
+    pub location: Uri,
+    pub body: T,
+}
+
+impl<T> Created<T> {
+    pub fn new(location: Uri, body: T) -> Self {
+        Created { location, body }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Created<T> {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::CREATED,
+            [(header::LOCATION, self.location.to_string())],
+            self.body,
+        )
+            .into_response()
+    }
+}
+
+// 204 No Content. field가 없으니 body를 넣을 방법 자체가 없다.
+pub struct NoContent;
+
+impl IntoResponse for NoContent {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::HttpBody;
+
+    use super::*;
+
+    #[test]
+    fn ok200_is_200_with_the_inner_body() {
+        let response = Ok200("hello").into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body().size_hint().exact(), Some(5));
+    }
+
+    #[test]
+    fn created_is_201_with_location() {
+        let uri: Uri = "/users/42?tab=profile".parse().unwrap();
+        let response = Created::new(uri.clone(), "made").into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::LOCATION], uri.to_string());
+        assert_eq!(response.headers().get_all(header::LOCATION).iter().count(), 1);
+    }
+
+    #[test]
+    fn no_content_is_204_with_empty_body() {
+        let response = NoContent.into_response();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.body().is_end_stream());
+        assert_eq!(response.body().size_hint().exact(), Some(0));
+    }
+}